@@ -1,4 +1,43 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use df_social_schedule::solver::Solver;
+
+/// Steps a `Schedule` with words of type `$w` through a depth first search
+macro_rules! bench_schedule_word {
+    ($c: expr, $w: ty, $name: expr) => {{
+        let schedule = schedule_solver::ScheduleBuilder::new(&[4; 6])
+            .build_with_word::<$w>()
+            .unwrap();
+        let block_size = schedule.get_block_size();
+        let mut buffer: Vec<$w> = vec![0; schedule.get_search_buffer_size()];
+        schedule.try_initialise_buffer(&mut buffer).unwrap();
+        let mut depth = 0;
+        $c.bench_function($name, |b| {
+            b.iter(|| {
+                let (buf_1, buf_2) = buffer[depth * block_size..].split_at_mut(block_size);
+                let result = black_box(schedule.step(buf_1, buf_2));
+                match result {
+                    Some(false) => depth += 1,
+                    None => depth -= 1,
+                    Some(true) => {}
+                }
+            })
+        });
+    }};
+}
+
+/// Steps a `DFScheduler` with words of type `$w` on `$groups`
+macro_rules! bench_df_word {
+    ($c: expr, $w: ty, $groups: expr, $name: expr) => {{
+        let mut scheduler = df_social_schedule::df_schedule::DFScheduler::<$w>::new($groups);
+        $c.bench_function($name, |b| b.iter(|| black_box(scheduler.step())));
+    }};
+}
+
+/// Steps any `Solver` through the common trait, with one round per table
+fn bench_solver<'a, S: Solver<'a>>(c: &mut Criterion, tables: &'a [usize], name: &str) {
+    let mut solver = S::configure(tables, tables.len()).unwrap();
+    c.bench_function(name, |b| b.iter(|| black_box(solver.step())));
+}
 
 fn criterion_benchmark(c: &mut Criterion) {
     let groups = [4; 6]
@@ -19,6 +58,32 @@ fn criterion_benchmark(c: &mut Criterion) {
 
     let mut scheduler = df_social_schedule::df_schedule::DFScheduler::<u8>::new(&groups);
     c.bench_function("u8 6 x 4", |b| b.iter(|| black_box(scheduler.step())));
+
+    bench_schedule_word!(c, u128, "Schedule u128 6 x 4");
+    bench_schedule_word!(c, u64, "Schedule u64 6 x 4");
+    bench_schedule_word!(c, u32, "Schedule u32 6 x 4");
+    bench_schedule_word!(c, usize, "Schedule usize 6 x 4");
+
+    bench_solver::<df_social_schedule::search::Search>(c, &[4; 6], "Solver Search 6 x 4");
+    bench_solver::<df_social_schedule::df_schedule::DFScheduler<u64>>(
+        c,
+        &[4; 6],
+        "Solver DFScheduler u64 6 x 4",
+    );
+
+    // Enough players that each bit set spans several words
+    let large_groups = [4; 40]
+        .iter()
+        .filter_map(|x| std::num::NonZeroUsize::new(*x))
+        .collect::<Vec<_>>();
+    bench_df_word!(c, u64, &large_groups, "u64 40 x 4");
+    bench_df_word!(c, u128, &large_groups, "u128 40 x 4");
+    #[cfg(feature = "simd")]
+    {
+        use schedule_solver::{SimdWord256, SimdWord512};
+        bench_df_word!(c, SimdWord256, &large_groups, "simd256 40 x 4");
+        bench_df_word!(c, SimdWord512, &large_groups, "simd512 40 x 4");
+    }
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,364 @@
+use crate::word::Word;
+
+/// A fixed number of bits, stored in words borrowed from a larger buffer
+///
+/// Bits from `len` up to the end of the last word are ignored. The operations are const, so
+/// they are implemented for each word type, and `Word` forwards to them for generic code.
+#[derive(Copy, Clone, Debug)]
+pub struct BitSet<'a, W: Word = usize> {
+    words: &'a [W],
+    len: usize,
+}
+
+#[derive(Debug)]
+pub struct BitSetMut<'a, W: Word = usize> {
+    words: &'a mut [W],
+    len: usize,
+}
+
+/// Number of words needed for `len` bits
+pub const fn word_count<W: Word>(len: usize) -> usize {
+    len / W::SIZE + (!len.is_multiple_of(W::SIZE)) as usize
+}
+
+impl<'a, W: Word> BitSet<'a, W> {
+    /// Panics if `words` has fewer than `len` bits
+    pub const fn new(words: &'a [W], len: usize) -> Self {
+        Self {
+            words: words.split_at(word_count::<W>(len)).0,
+            len,
+        }
+    }
+
+    pub const fn get_len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn get_words(&self) -> &'a [W] {
+        self.words
+    }
+
+    /// Set bits in increasing order
+    pub fn iter(&self) -> BitSetIter<'a, W> {
+        BitSetIter {
+            set: *self,
+            next: 0,
+        }
+    }
+}
+
+impl<'a, W: Word> BitSetMut<'a, W> {
+    /// Panics if `words` has fewer than `len` bits
+    pub const fn new(words: &'a mut [W], len: usize) -> Self {
+        Self {
+            words: words.split_at_mut(word_count::<W>(len)).0,
+            len,
+        }
+    }
+
+    pub const fn get_len(&self) -> usize {
+        self.len
+    }
+
+    #[cfg(feature = "simd")]
+    pub(crate) fn get_words_mut(&mut self) -> &mut [W] {
+        self.words
+    }
+
+    pub const fn as_bitset(&self) -> BitSet<'_, W> {
+        BitSet {
+            words: self.words,
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, W: Word> IntoIterator for BitSet<'a, W> {
+    type Item = usize;
+    type IntoIter = BitSetIter<'a, W>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct BitSetIter<'a, W: Word = usize> {
+    set: BitSet<'a, W>,
+    next: usize,
+}
+
+impl<'a, W: Word> Iterator for BitSetIter<'a, W> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let bit = W::first_set_in(self.set, self.next)?;
+        self.next = bit + 1;
+        Some(bit)
+    }
+}
+
+macro_rules! impl_bitset {
+    ($w: ty) => {
+        impl<'a> BitSet<'a, $w> {
+            /// The last word with the bits from `len` onwards cleared
+            const fn get_masked_word(&self, index: usize) -> $w {
+                let remainder = self.len % <$w>::BITS as usize;
+                if index + 1 == self.words.len() && remainder != 0 {
+                    self.words[index] & !(!(0 as $w) << remainder)
+                } else {
+                    self.words[index]
+                }
+            }
+
+            pub const fn count_ones(&self) -> usize {
+                let mut count = 0;
+                let mut index = 0;
+                while index < self.words.len() {
+                    count += self.get_masked_word(index).count_ones() as usize;
+                    index += 1;
+                }
+                count
+            }
+
+            pub const fn is_empty(&self) -> bool {
+                self.first_set_from(0).is_none()
+            }
+
+            pub const fn contains(&self, bit: usize) -> bool {
+                bit < self.len
+                    && (self.words[bit / <$w>::BITS as usize] >> (bit % <$w>::BITS as usize)) & 1
+                        != 0
+            }
+
+            /// The first set bit at or after `start`
+            pub const fn first_set_from(&self, start: usize) -> Option<usize> {
+                if start >= self.len {
+                    return None;
+                }
+                let mut index = start / <$w>::BITS as usize;
+                let mut word =
+                    self.get_masked_word(index) & (!(0 as $w) << (start % <$w>::BITS as usize));
+                loop {
+                    if word != 0 {
+                        return Some(index * <$w>::BITS as usize + word.trailing_zeros() as usize);
+                    }
+                    index += 1;
+                    if index >= self.words.len() {
+                        return None;
+                    }
+                    word = self.get_masked_word(index);
+                }
+            }
+
+            /// The first unset bit at or after `start`
+            pub const fn first_unset_from(&self, start: usize) -> Option<usize> {
+                let mut bit = start;
+                while bit < self.len {
+                    let word =
+                        !self.words[bit / <$w>::BITS as usize] >> (bit % <$w>::BITS as usize);
+                    if word == 0 {
+                        bit = (bit / <$w>::BITS as usize + 1) * <$w>::BITS as usize;
+                        continue;
+                    }
+                    bit += word.trailing_zeros() as usize;
+                    return if bit < self.len { Some(bit) } else { None };
+                }
+                None
+            }
+
+            /// The first bit at or after `start` which is set in `self` but not in `other`, which must
+            /// be at least as long
+            pub const fn first_difference_from(
+                &self,
+                other: &BitSet<'_, $w>,
+                start: usize,
+            ) -> Option<usize> {
+                if start >= self.len {
+                    return None;
+                }
+                let mut index = start / <$w>::BITS as usize;
+                let mut word = (self.get_masked_word(index) & !other.words[index])
+                    & (!(0 as $w) << (start % <$w>::BITS as usize));
+                loop {
+                    if word != 0 {
+                        return Some(index * <$w>::BITS as usize + word.trailing_zeros() as usize);
+                    }
+                    index += 1;
+                    if index >= self.words.len() {
+                        return None;
+                    }
+                    word = self.get_masked_word(index) & !other.words[index];
+                }
+            }
+
+            /// Bits set in both `self` and `other`
+            pub const fn intersection_count(&self, other: &BitSet<'_, $w>) -> usize {
+                let mut count = 0;
+                let mut index = 0;
+                while index < self.words.len() && index < other.words.len() {
+                    count += (self.get_masked_word(index) & other.get_masked_word(index))
+                        .count_ones() as usize;
+                    index += 1;
+                }
+                count
+            }
+
+            pub const fn is_disjoint(&self, other: &BitSet<'_, $w>) -> bool {
+                let mut index = 0;
+                while index < self.words.len() && index < other.words.len() {
+                    if self.get_masked_word(index) & other.get_masked_word(index) != 0 {
+                        return false;
+                    }
+                    index += 1;
+                }
+                true
+            }
+        }
+
+        impl<'a> BitSetMut<'a, $w> {
+            pub const fn insert(&mut self, bit: usize) {
+                assert!(bit < self.len);
+                self.words[bit / <$w>::BITS as usize] |= 1 << (bit % <$w>::BITS as usize);
+            }
+
+            pub const fn remove(&mut self, bit: usize) {
+                assert!(bit < self.len);
+                self.words[bit / <$w>::BITS as usize] &= !(1 << (bit % <$w>::BITS as usize));
+            }
+
+            /// Keeps only the bits also set in `other`
+            pub const fn intersect_with(&mut self, other: &BitSet<'_, $w>) {
+                let mut index = 0;
+                while index < self.words.len() {
+                    self.words[index] &= if index < other.words.len() {
+                        other.words[index]
+                    } else {
+                        0
+                    };
+                    index += 1;
+                }
+            }
+
+            pub const fn union_with(&mut self, other: &BitSet<'_, $w>) {
+                let mut index = 0;
+                while index < self.words.len() && index < other.words.len() {
+                    self.words[index] |= other.get_masked_word(index);
+                    index += 1;
+                }
+            }
+
+            /// Clears the bits set in `other`
+            pub const fn subtract(&mut self, other: &BitSet<'_, $w>) {
+                let mut index = 0;
+                while index < self.words.len() && index < other.words.len() {
+                    self.words[index] &= !other.words[index];
+                    index += 1;
+                }
+            }
+        }
+    };
+}
+
+impl_bitset!(u8);
+impl_bitset!(u16);
+impl_bitset!(u32);
+impl_bitset!(u64);
+impl_bitset!(u128);
+impl_bitset!(usize);
+impl_bitset!(i8);
+impl_bitset!(i16);
+impl_bitset!(i32);
+impl_bitset!(i64);
+impl_bitset!(i128);
+impl_bitset!(isize);
+
+#[cfg(test)]
+mod tests {
+    use super::{BitSet, BitSetMut};
+
+    #[test]
+    fn insert_and_remove_across_words() {
+        let mut words = [0u8; 3];
+        let mut set = BitSetMut::new(&mut words, 20);
+        for bit in [0, 7, 8, 15, 16, 19] {
+            set.insert(bit);
+        }
+        set.remove(8);
+        assert_eq!(words, [0b1000_0001, 0b1000_0000, 0b0000_1001]);
+        let set = BitSet::new(&words, 20);
+        assert!(set.contains(7) && set.contains(15) && set.contains(16) && set.contains(19));
+        assert!(!set.contains(8) && !set.contains(20));
+        assert_eq!(set.count_ones(), 5);
+    }
+
+    #[test]
+    fn bits_past_len_are_ignored() {
+        let words = [u8::MAX; 2];
+        let set = BitSet::new(&words, 12);
+        assert_eq!(set.count_ones(), 12);
+        assert!(!set.contains(12));
+        assert_eq!(set.first_set_from(11), Some(11));
+        assert_eq!(set.first_set_from(12), None);
+        let empty = [0u8; 2];
+        assert_eq!(BitSet::new(&empty, 12).first_unset_from(11), Some(11));
+        assert_eq!(set.first_unset_from(0), None);
+    }
+
+    #[test]
+    fn first_set_across_words() {
+        let words = [0u64, 0, 1 << 3];
+        let set = BitSet::new(&words, 150);
+        assert_eq!(set.first_set_from(0), Some(131));
+        assert_eq!(set.first_set_from(131), Some(131));
+        assert_eq!(set.first_set_from(132), None);
+        assert!(!set.is_empty());
+        let words = [u64::MAX, u64::MAX, 0];
+        assert_eq!(BitSet::new(&words, 150).first_unset_from(3), Some(128));
+    }
+
+    #[test]
+    fn first_difference_across_words() {
+        let words = [0b0110u8, 0b0001, 0b0100];
+        let other = [0b0110u8, 0b0000, 0b0000];
+        let set = BitSet::new(&words, 24);
+        assert_eq!(
+            set.first_difference_from(&BitSet::new(&other, 24), 0),
+            Some(8)
+        );
+        assert_eq!(
+            set.first_difference_from(&BitSet::new(&other, 24), 9),
+            Some(18)
+        );
+    }
+
+    #[test]
+    fn disjoint_and_intersection_across_words() {
+        let a = [0b0001u8, 0b1000, 0b0001];
+        let b = [0b0010u8, 0b0100, 0b0001];
+        let c = [0b0010u8, 0b0100, 0b0000];
+        assert!(!BitSet::new(&a, 17).is_disjoint(&BitSet::new(&b, 17)));
+        assert_eq!(
+            BitSet::new(&a, 17).intersection_count(&BitSet::new(&b, 17)),
+            1
+        );
+        assert!(BitSet::new(&a, 17).is_disjoint(&BitSet::new(&c, 17)));
+        // Only the bit past `len` is shared
+        let d = [0u8, 0, 0b0010];
+        let e = [0u8, 0, 0b0010];
+        assert!(BitSet::new(&d, 17).is_disjoint(&BitSet::new(&e, 17)));
+    }
+
+    #[test]
+    fn set_operations_across_words() {
+        let mut words = [0b1111u8, 0b1111, 0b1111];
+        let other = [0b0101u8, 0b0000, 0b1010];
+        let mut set = BitSetMut::new(&mut words, 24);
+        set.intersect_with(&BitSet::new(&other, 24));
+        assert_eq!(words, [0b0101, 0b0000, 0b1010]);
+        let mut set = BitSetMut::new(&mut words, 24);
+        set.union_with(&BitSet::new(&[0u8, 0b0001, 0b0001], 24));
+        set.subtract(&BitSet::new(&[0b0001u8, 0, 0b1000], 24));
+        assert_eq!(words, [0b0100, 0b0001, 0b0011]);
+    }
+}
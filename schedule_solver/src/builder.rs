@@ -0,0 +1,100 @@
+#[cfg(feature = "alloc")]
+use crate::owned::OwnedSchedule;
+use crate::schedule::{Lookahead, Schedule, ScheduleErrors};
+use crate::word::ScheduleWord;
+
+/// Validated construction of a `Schedule`
+#[derive(Copy, Clone, Debug)]
+pub struct ScheduleBuilder<'a> {
+    tables: &'a [usize],
+    rounds: usize,
+    lookahead: Lookahead,
+}
+
+impl<'a> ScheduleBuilder<'a> {
+    /// Starts with one round per table and no lookahead
+    pub const fn new(tables: &'a [usize]) -> Self {
+        Self {
+            tables,
+            rounds: tables.len(),
+            lookahead: Lookahead::DISABLED,
+        }
+    }
+
+    pub const fn rounds(mut self, rounds: usize) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
+    pub const fn lookahead(mut self, lookahead: Lookahead) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
+    pub const fn build(self) -> Result<Schedule<'a>, ScheduleErrors> {
+        self.build_with_word()
+    }
+
+    /// Builds a `Schedule` whose blocks use words of type `W`
+    ///
+    /// `W` is one of the words `Schedule` is implemented for, so narrower words do not build:
+    ///
+    /// ```compile_fail
+    /// schedule_solver::ScheduleBuilder::new(&[2; 3]).build_with_word::<u16>();
+    /// ```
+    pub const fn build_with_word<W: ScheduleWord>(self) -> Result<Schedule<'a, W>, ScheduleErrors> {
+        if self.tables.is_empty() {
+            return Err(ScheduleErrors::NoTables);
+        }
+        let mut player_count: usize = 0;
+        let mut table = 0;
+        while table < self.tables.len() {
+            if self.tables[table] == 0 {
+                return Err(ScheduleErrors::ZeroLengthGroups { table });
+            }
+            player_count = if let Some(player_count) = player_count.checked_add(self.tables[table])
+            {
+                player_count
+            } else {
+                return Err(ScheduleErrors::PlayerCountOverflow { table });
+            };
+            table += 1;
+        }
+        if self.rounds == 0 {
+            return Err(ScheduleErrors::ZeroRounds);
+        }
+        if self.rounds > self.tables.len() {
+            return Err(ScheduleErrors::RoundsTooLarge {
+                rounds: self.rounds,
+                limit: self.tables.len(),
+            });
+        }
+        // played_with is the largest part of a block, at player_count squared bits
+        if player_count.checked_mul(player_count).is_none() {
+            return Err(ScheduleErrors::PlayerCountOverflow {
+                table: self.tables.len() - 1,
+            });
+        }
+        // The players placed counter is stored in a single word
+        let word_bits = core::mem::size_of::<W>() * 8;
+        if word_bits < usize::BITS as usize && (player_count * self.rounds) >> word_bits != 0 {
+            return Err(ScheduleErrors::PlayerCountOverflow {
+                table: self.tables.len() - 1,
+            });
+        }
+        Ok(Schedule::new(self.tables, self.rounds).with_lookahead(self.lookahead))
+    }
+
+    /// Builds an `OwnedSchedule`, which copies the tables so they need not outlive it
+    #[cfg(feature = "alloc")]
+    pub fn build_owned(self) -> Result<OwnedSchedule, ScheduleErrors> {
+        self.build_owned_with_word()
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn build_owned_with_word<W: ScheduleWord>(
+        self,
+    ) -> Result<OwnedSchedule<W>, ScheduleErrors> {
+        Ok(OwnedSchedule::from(&self.build_with_word::<W>()?))
+    }
+}
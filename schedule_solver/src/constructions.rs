@@ -0,0 +1,267 @@
+//! Closed-form schedules for layouts which do not need a search.
+//!
+//! The only construction is a truncated affine plane, for q tables of k players each, where q
+//! is a prime power, k < q and there are at most q rounds. Every player may only sit at each
+//! table once, so rounds taken from a full affine plane of order q (k = q) always put some
+//! player back on the same table. Keeping only k of the points on each line avoids that:
+//! player `g * k + s` sits at table `g + r * x_s` in round r, where `x_s` is the (s + 1)th
+//! element of GF(q), which is never zero. Players with the same seat never meet, and any other
+//! pair meets exactly once in q rounds, which is a resolvable transversal design.
+//!
+//! So five tables of three or of two are covered, but three tables of three, six tables of any
+//! size and tables of different sizes are left to the search.
+//!
+//! There is no separate Kirkman triple system construction. A KTS on v players has (v - 1) / 2
+//! rounds but only v / 3 tables, and a schedule has at most as many rounds as tables. When v / 3
+//! is a prime power of at least 5, the truncated affine plane already reaches that limit. KTS(9)
+//! cannot even be cut to two rounds, as each triple of the second round holds a player from every
+//! table of the first. That leaves v = 45, 63, 99 and so on, whose systems need the general
+//! recursive constructions, to the search as well.
+
+use crate::schedule::Schedule;
+use crate::word::{for_each_schedule_word, Word};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Construction {
+    /// `order` tables of `table_size` players, taken from the affine plane of order `order`
+    AffinePlane { order: usize, table_size: usize },
+}
+
+/// GF(p^n), with elements stored as their base p coefficients
+#[derive(Copy, Clone, Debug)]
+struct GaloisField {
+    prime: usize,
+    degree: usize,
+    order: usize,
+    /// Lower coefficients of a monic irreducible polynomial of degree `degree`
+    modulus: usize,
+}
+
+impl GaloisField {
+    const fn new(order: usize) -> Option<Self> {
+        if order < 2 {
+            return None;
+        }
+        let mut prime = 2;
+        while !order.is_multiple_of(prime) {
+            prime += 1;
+        }
+        let mut degree = 0;
+        let mut remaining = order;
+        while remaining.is_multiple_of(prime) {
+            remaining /= prime;
+            degree += 1;
+        }
+        if remaining != 1 {
+            return None;
+        }
+        let mut field = Self {
+            prime,
+            degree,
+            order,
+            modulus: 0,
+        };
+        if degree == 1 {
+            return Some(field);
+        }
+        while field.modulus < order {
+            if field.has_no_zero_divisors() {
+                return Some(field);
+            }
+            field.modulus += 1;
+        }
+        None
+    }
+
+    const fn has_no_zero_divisors(&self) -> bool {
+        let mut a = 1;
+        while a < self.order {
+            let mut b = 1;
+            while b < self.order {
+                if self.mul(a, b) == 0 {
+                    return false;
+                }
+                b += 1;
+            }
+            a += 1;
+        }
+        true
+    }
+
+    const fn add(&self, mut a: usize, mut b: usize) -> usize {
+        let mut result = 0;
+        let mut place = 1;
+        let mut i = 0;
+        while i < self.degree {
+            result += ((a % self.prime + b % self.prime) % self.prime) * place;
+            a /= self.prime;
+            b /= self.prime;
+            place *= self.prime;
+            i += 1;
+        }
+        result
+    }
+
+    const fn scale(&self, mut a: usize, scalar: usize) -> usize {
+        let mut result = 0;
+        let mut place = 1;
+        let mut i = 0;
+        while i < self.degree {
+            result += ((a % self.prime) * scalar % self.prime) * place;
+            a /= self.prime;
+            place *= self.prime;
+            i += 1;
+        }
+        result
+    }
+
+    const fn mul(&self, a: usize, b: usize) -> usize {
+        if self.degree == 1 {
+            return a * b % self.prime;
+        }
+        let top_place = self.order / self.prime;
+        let mut result = 0;
+        let mut place = top_place;
+        while place > 0 {
+            // result = result * x, with x^degree replaced by -modulus
+            let top = result / top_place;
+            result = (result % top_place) * self.prime;
+            result = self.add(result, self.scale(self.modulus, self.prime - top));
+            result = self.add(result, self.scale(a, b / place % self.prime));
+            place /= self.prime;
+        }
+        result
+    }
+}
+
+impl<'a, W: Word> Schedule<'a, W> {
+    pub const fn find_construction(&self) -> Option<Construction> {
+        let tables = self.get_tables();
+        if tables.is_empty() {
+            return None;
+        }
+        let table_size = tables[0];
+        let mut i = 1;
+        while i < tables.len() {
+            if tables[i] != table_size {
+                return None;
+            }
+            i += 1;
+        }
+        let order = tables.len();
+        if table_size >= order || self.get_rounds() > order || GaloisField::new(order).is_none() {
+            return None;
+        }
+        Some(Construction::AffinePlane { order, table_size })
+    }
+}
+
+macro_rules! impl_construct {
+    ($w: ty) => {
+        impl<'a> Schedule<'a, $w> {
+            /// Fills `buffer` with a complete schedule if `find_construction` finds one
+            ///
+            /// Round 0 matches the layout from `initialise_buffer`
+            #[must_use]
+            pub const fn construct(&self, buffer: &mut [$w]) -> Option<Construction> {
+                let construction = if let Some(construction) = self.find_construction() {
+                    construction
+                } else {
+                    return None;
+                };
+                if !self.initialise_buffer(buffer) {
+                    return None;
+                }
+                match construction {
+                    Construction::AffinePlane { order, table_size } => {
+                        let field = if let Some(field) = GaloisField::new(order) {
+                            field
+                        } else {
+                            return None;
+                        };
+                        let mut round = 1;
+                        while round < self.get_rounds() {
+                            let mut player = 0;
+                            while player < self.get_player_count() {
+                                let group = player / table_size;
+                                let seat = player % table_size;
+                                let table = field.add(group, field.mul(round, seat + 1));
+                                if self.apply_player_at(buffer, round, table, player).is_none() {
+                                    return None;
+                                }
+                                player += 1;
+                            }
+                            round += 1;
+                        }
+                    }
+                }
+                self.close_full_tables(buffer);
+                Some(construction)
+            }
+        }
+    };
+}
+
+for_each_schedule_word!(impl_construct);
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::Construction;
+    use crate::builder::ScheduleBuilder;
+    use crate::verify::{verify_seats, Constraints};
+    use alloc::vec;
+
+    #[test]
+    fn constructions_verify() {
+        for order in [2, 3, 4, 5, 7, 8, 9] {
+            for table_size in 1..order {
+                let tables = vec![table_size; order];
+                let schedule = ScheduleBuilder::new(&tables)
+                    .rounds(order)
+                    .build_with_word::<u64>()
+                    .unwrap();
+                let mut buffer = vec![0; schedule.get_block_size()];
+                assert_eq!(
+                    schedule.construct(&mut buffer),
+                    Some(Construction::AffinePlane { order, table_size })
+                );
+                let solution = schedule.to_solution(&buffer).unwrap();
+                let violations =
+                    verify_seats(&tables, solution.get_seats(), &Constraints::default());
+                assert!(violations.is_empty(), "{:?}: {:?}", tables, violations);
+            }
+        }
+    }
+
+    /// Tables of three for 6t + 3 players, the sizes of Kirkman triple systems
+    #[test]
+    fn kirkman_sizes() {
+        let constructed = [15, 21, 27, 33, 39, 51, 57, 69, 75, 81, 87, 93];
+        for players in (9..=99).step_by(6) {
+            let tables = vec![3; players / 3];
+            let schedule = ScheduleBuilder::new(&tables)
+                .rounds(players / 3)
+                .build()
+                .unwrap();
+            assert_eq!(
+                schedule.find_construction().is_some(),
+                constructed.contains(&players),
+                "{}",
+                players
+            );
+        }
+
+        let tables = [3; 3];
+        let schedule = ScheduleBuilder::new(&tables).rounds(2).build().unwrap();
+        let mut buffer = vec![0; schedule.get_search_buffer_size()];
+        assert!(schedule.fixed_search(&mut buffer).unwrap().solve().is_err());
+    }
+
+    #[test]
+    fn no_construction_outside_truncated_planes() {
+        for tables in [&[3; 3][..], &[2; 6], &[3, 3, 2, 3]] {
+            let schedule = ScheduleBuilder::new(tables).build().unwrap();
+            assert_eq!(schedule.find_construction(), None, "{:?}", tables);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::schedule::Schedule;
+use crate::word::Word;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Infeasibility {
+    /// Each player needs a different table in every round
+    TooManyRounds { rounds: usize, tables: usize },
+    /// Every seat at `table` across all rounds needs a different player
+    TableOverbooked {
+        table: usize,
+        seats: usize,
+        player_count: usize,
+    },
+    /// Even sitting at the smallest tables, a player needs more opponents than there are players
+    TooFewOpponents {
+        rounds: usize,
+        needed: usize,
+        available: usize,
+    },
+    /// The tables create more meetings than there are pairs of players
+    TooManyMeetings { meetings: usize, pairs: usize },
+    /// The search ran out of options after `steps` steps
+    SearchExhausted { steps: usize },
+}
+
+impl core::fmt::Display for Infeasibility {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooManyRounds { rounds, tables } => write!(
+                f,
+                "{} rounds need at least {} tables, but there are only {}",
+                rounds, rounds, tables
+            ),
+            Self::TableOverbooked {
+                table,
+                seats,
+                player_count,
+            } => write!(
+                f,
+                "table {} has {} seats over all rounds, but there are only {} players",
+                table, seats, player_count
+            ),
+            Self::TooFewOpponents {
+                rounds,
+                needed,
+                available,
+            } => write!(
+                f,
+                "each player meets at least {} opponents in {} rounds, but there are only {}",
+                needed, rounds, available
+            ),
+            Self::TooManyMeetings { meetings, pairs } => write!(
+                f,
+                "the schedule needs {} meetings, but there are only {} pairs of players",
+                meetings, pairs
+            ),
+            Self::SearchExhausted { steps } => {
+                write!(f, "no valid schedule exists (searched {} steps)", steps)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Infeasibility {}
+
+impl<'a, W: Word> Schedule<'a, W> {
+    /// Cheap necessary conditions, checked before searching
+    pub const fn check_feasibility(&self) -> Result<(), Infeasibility> {
+        let tables = self.get_tables();
+        let rounds = self.get_rounds();
+        let player_count = self.get_player_count();
+        if rounds > tables.len() {
+            return Err(Infeasibility::TooManyRounds {
+                rounds,
+                tables: tables.len(),
+            });
+        }
+
+        let mut meetings = 0;
+        let mut table = 0;
+        while table < tables.len() {
+            let seats = tables[table] * rounds;
+            if seats > player_count {
+                return Err(Infeasibility::TableOverbooked {
+                    table,
+                    seats,
+                    player_count,
+                });
+            }
+            meetings += rounds * tables[table] * tables[table].saturating_sub(1) / 2;
+            table += 1;
+        }
+
+        // Opponents met at the `rounds` smallest tables, taking each distinct size in turn
+        let mut needed = 0;
+        let mut remaining = rounds;
+        let mut size = 0;
+        while remaining > 0 {
+            let mut next_size = usize::MAX;
+            let mut count = 0;
+            let mut table = 0;
+            while table < tables.len() {
+                if tables[table] >= size && tables[table] < next_size {
+                    next_size = tables[table];
+                    count = 0;
+                }
+                if tables[table] == next_size {
+                    count += 1;
+                }
+                table += 1;
+            }
+            if count == 0 {
+                break;
+            }
+            let taken = if count < remaining { count } else { remaining };
+            needed += taken * next_size.saturating_sub(1);
+            remaining -= taken;
+            size = next_size + 1;
+        }
+        let available = player_count.saturating_sub(1);
+        if needed > available {
+            return Err(Infeasibility::TooFewOpponents {
+                rounds,
+                needed,
+                available,
+            });
+        }
+
+        let pairs = player_count * available / 2;
+        if meetings > pairs {
+            return Err(Infeasibility::TooManyMeetings { meetings, pairs });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+use crate::feasibility::Infeasibility;
+use crate::schedule::{Schedule, ScheduleErrors};
+use crate::stats::SearchStats;
+use crate::word::{for_each_schedule_word, Word};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SearchStep {
+    Advanced,
+    Backtracked,
+    Solved,
+}
+
+impl<'a, W: Word> Schedule<'a, W> {
+    /// The most blocks a depth first search has on its stack, counting the block `step` writes
+    /// into
+    ///
+    /// Each step forward places at least one player, and the first round starts out placed, so
+    /// there are at most `player_count * (rounds - 1)` steps forward, each pushing one block.
+    /// The `+ 2` is the starting block and the block the next `step` writes into.
+    pub const fn get_max_depth(&self) -> usize {
+        self.get_player_count() * self.get_rounds().saturating_sub(1) + 2
+    }
+
+    /// Words of buffer needed by `fixed_search`
+    pub const fn get_search_buffer_size(&self) -> usize {
+        self.get_block_size() * self.get_max_depth()
+    }
+}
+
+/// Depth first search over a stack of blocks in a caller-provided buffer, for use without an
+/// allocator
+#[derive(Debug)]
+pub struct FixedSearch<'b, 'a, W: Word = usize> {
+    schedule: &'b Schedule<'a, W>,
+    buffer: &'b mut [W],
+    depth: usize,
+    steps: usize,
+    stats: SearchStats,
+}
+
+impl<'b, 'a, W: Word> FixedSearch<'b, 'a, W> {
+    pub const fn get_scheduler(&self) -> &'b Schedule<'a, W> {
+        self.schedule
+    }
+
+    pub const fn get_depth(&self) -> usize {
+        self.depth
+    }
+
+    pub const fn get_steps(&self) -> usize {
+        self.steps
+    }
+
+    /// `elapsed` is left at zero, as there is no clock without `std`
+    pub const fn get_stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    /// The block at the current depth
+    pub const fn get_current(&self) -> &[W] {
+        let block_size = self.schedule.get_block_size();
+        let (_, current) = self.buffer.split_at(self.depth * block_size);
+        current.split_at(block_size).0
+    }
+}
+
+macro_rules! impl_fixed_search {
+    ($w: ty) => {
+        impl<'a> Schedule<'a, $w> {
+            /// Starts a search in `buffer`, which must hold at least `get_search_buffer_size()`
+            /// words
+            pub const fn fixed_search<'b>(
+                &'b self,
+                buffer: &'b mut [$w],
+            ) -> Result<FixedSearch<'b, 'a, $w>, ScheduleErrors> {
+                if buffer.len() < self.get_search_buffer_size() {
+                    return Err(ScheduleErrors::TooSmallBuffer {
+                        needed: self.get_search_buffer_size(),
+                        len: buffer.len(),
+                    });
+                }
+                if let Err(error) = self.try_initialise_buffer(buffer) {
+                    return Err(error);
+                }
+                Ok(self.fixed_search_from(buffer))
+            }
+
+            /// Starts a search from the block already at the start of `buffer`, which the caller
+            /// has checked is long enough
+            pub(crate) const fn fixed_search_from<'b>(
+                &'b self,
+                buffer: &'b mut [$w],
+            ) -> FixedSearch<'b, 'a, $w> {
+                FixedSearch {
+                    schedule: self,
+                    buffer,
+                    depth: 0,
+                    steps: 0,
+                    stats: SearchStats::new(),
+                }
+            }
+
+            /// Searches in a `BUFFER` word array of its own, returning a copy of the solved block
+            ///
+            /// This is meant for building constants, where an infeasible layout can be turned into
+            /// a compile error. Panics if `BUFFER` is less than `get_search_buffer_size()` or
+            /// `BLOCK` is not `get_block_size()`.
+            ///
+            /// Const evaluation is far slower than running the search, and any but the smallest
+            /// layouts, such as five tables of three for three rounds, run into the deny by
+            /// default `long_running_const_eval` lint. The constant then needs
+            /// `#[allow(long_running_const_eval)]` to build.
+            pub const fn solve_to_array<const BUFFER: usize, const BLOCK: usize>(
+                &self,
+            ) -> Result<[$w; BLOCK], Infeasibility> {
+                assert!(BLOCK == self.get_block_size());
+                let mut buffer = [0; BUFFER];
+                let mut search = match self.fixed_search(&mut buffer) {
+                    Ok(search) => search,
+                    Err(_) => panic!("BUFFER is smaller than get_search_buffer_size()"),
+                };
+                let solved = match search.solve() {
+                    Ok(solved) => solved,
+                    Err(reason) => return Err(reason),
+                };
+                let mut block = [0; BLOCK];
+                let mut i = 0;
+                while i < BLOCK {
+                    block[i] = solved[i];
+                    i += 1;
+                }
+                Ok(block)
+            }
+        }
+
+        impl<'b, 'a> FixedSearch<'b, 'a, $w> {
+            pub const fn step(&mut self) -> Result<SearchStep, Infeasibility> {
+                let block_size = self.schedule.get_block_size();
+                let (_, current) = self.buffer.split_at_mut(self.depth * block_size);
+                let (buf_1, buf_2) = current.split_at_mut(block_size);
+                let result = match self.schedule.step_with_stats(buf_1, buf_2, &mut self.stats) {
+                    Some(true) => SearchStep::Solved,
+                    Some(false) => {
+                        self.depth += 1;
+                        if self.depth > self.stats.max_depth {
+                            self.stats.max_depth = self.depth;
+                        }
+                        SearchStep::Advanced
+                    }
+                    None => {
+                        self.depth = match self.depth.checked_sub(1) {
+                            Some(depth) => depth,
+                            None => {
+                                return Err(Infeasibility::SearchExhausted { steps: self.steps })
+                            }
+                        };
+                        SearchStep::Backtracked
+                    }
+                };
+                self.steps += 1;
+                Ok(result)
+            }
+
+            /// Steps until a schedule is found
+            pub const fn solve(&mut self) -> Result<&[$w], Infeasibility> {
+                loop {
+                    match self.step() {
+                        Ok(SearchStep::Solved) => return Ok(self.get_current()),
+                        Ok(_) => {}
+                        Err(reason) => return Err(reason),
+                    }
+                }
+            }
+
+            /// Steps until a schedule is found or `max_steps` more steps have been taken,
+            /// returning `Ok(None)` in the second case
+            ///
+            /// The search can be continued afterwards, for example to keep a display responsive.
+            pub const fn solve_with_step_limit(
+                &mut self,
+                max_steps: usize,
+            ) -> Result<Option<&[$w]>, Infeasibility> {
+                let mut steps = 0;
+                while steps < max_steps {
+                    match self.step() {
+                        Ok(SearchStep::Solved) => return Ok(Some(self.get_current())),
+                        Ok(_) => {}
+                        Err(reason) => return Err(reason),
+                    }
+                    steps += 1;
+                }
+                Ok(None)
+            }
+        }
+    };
+}
+
+for_each_schedule_word!(impl_fixed_search);
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::builder::ScheduleBuilder;
+    use crate::schedule::Schedule;
+    use crate::verify::{verify_seats, Constraints};
+
+    const TABLES: [usize; 4] = [3; 4];
+    const SCHEDULE: Schedule<'static, u64> =
+        match ScheduleBuilder::new(&TABLES).rounds(3).build_with_word() {
+            Ok(schedule) => schedule,
+            Err(_) => panic!("Invalid tables"),
+        };
+    /// Solved by the compiler, well inside the const evaluation limit
+    const SOLVED: [u64; SCHEDULE.get_block_size()] = match SCHEDULE
+        .solve_to_array::<{ SCHEDULE.get_search_buffer_size() }, { SCHEDULE.get_block_size() }>()
+    {
+        Ok(block) => block,
+        Err(_) => panic!("No schedule"),
+    };
+
+    #[test]
+    fn solves_in_const() {
+        let solution = SCHEDULE.to_solution(&SOLVED).unwrap();
+        let violations = verify_seats(&TABLES, solution.get_seats(), &Constraints::default());
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+}
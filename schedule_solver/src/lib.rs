@@ -1,6 +1,48 @@
 #![no_std]
 #![feature(const_mut_refs)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod bitset;
+mod builder;
+mod constructions;
+mod feasibility;
+mod fixed_search;
+#[cfg(feature = "alloc")]
+mod owned;
+#[cfg(feature = "alloc")]
+mod repair;
 mod schedule;
+#[cfg(feature = "simd")]
+mod simd_word;
+#[cfg(feature = "alloc")]
+mod solution;
+mod stats;
 mod util;
+#[cfg(feature = "alloc")]
+mod verify;
+mod view;
+mod word;
+pub use bitset::*;
+pub use builder::*;
+pub use constructions::*;
+pub use feasibility::*;
+pub use fixed_search::*;
+#[cfg(feature = "alloc")]
+pub use owned::*;
+#[cfg(feature = "alloc")]
+pub use repair::*;
 pub use schedule::*;
+#[cfg(feature = "simd")]
+pub use simd_word::*;
+#[cfg(feature = "alloc")]
+pub use solution::*;
+pub use stats::*;
+#[cfg(feature = "alloc")]
+pub use verify::*;
+pub use view::*;
+pub use word::{ScheduleWord, Word};
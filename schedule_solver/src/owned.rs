@@ -0,0 +1,217 @@
+use crate::builder::ScheduleBuilder;
+use crate::schedule::{Lookahead, Schedule, ScheduleErrors};
+use crate::word::{ScheduleWord, Word};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// A validated configuration which owns its tables, for schedules read at runtime and kept in
+/// structs or sent between threads
+///
+/// `Schedule` borrows its tables so that it can be built in const code, so one is borrowed from
+/// here with `as_schedule` when searching.
+///
+/// With the `serde` feature it is stored as its `tables`, `rounds` and `lookahead`, which are
+/// checked again when read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "ScheduleConfig", bound(deserialize = "W: ScheduleWord"))
+)]
+pub struct OwnedSchedule<W: Word = usize> {
+    tables: Vec<usize>,
+    rounds: usize,
+    lookahead: Lookahead,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _word: PhantomData<W>,
+}
+
+/// The stored form of an `OwnedSchedule`
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct ScheduleConfig {
+    tables: Vec<usize>,
+    rounds: usize,
+    lookahead: Lookahead,
+}
+
+#[cfg(feature = "serde")]
+impl<W: ScheduleWord> core::convert::TryFrom<ScheduleConfig> for OwnedSchedule<W> {
+    type Error = ScheduleErrors;
+
+    fn try_from(config: ScheduleConfig) -> Result<Self, Self::Error> {
+        Ok(Self::new(config.tables, config.rounds)?.with_lookahead(config.lookahead))
+    }
+}
+
+impl<W: ScheduleWord> OwnedSchedule<W> {
+    /// Checked as by `ScheduleBuilder`
+    pub fn new(tables: Vec<usize>, rounds: usize) -> Result<Self, ScheduleErrors> {
+        ScheduleBuilder::new(&tables)
+            .rounds(rounds)
+            .build_with_word::<W>()?;
+        Ok(Self {
+            tables,
+            rounds,
+            lookahead: Lookahead::DISABLED,
+            _word: PhantomData,
+        })
+    }
+}
+
+impl<W: Word> OwnedSchedule<W> {
+    pub fn with_lookahead(mut self, lookahead: Lookahead) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
+    pub fn as_schedule(&self) -> Schedule<'_, W> {
+        Schedule::new(&self.tables, self.rounds).with_lookahead(self.lookahead)
+    }
+
+    pub fn get_tables(&self) -> &[usize] {
+        &self.tables
+    }
+
+    pub fn get_rounds(&self) -> usize {
+        self.rounds
+    }
+
+    pub fn get_lookahead(&self) -> Lookahead {
+        self.lookahead
+    }
+}
+
+impl<W: Word> From<&Schedule<'_, W>> for OwnedSchedule<W> {
+    fn from(schedule: &Schedule<'_, W>) -> Self {
+        Self {
+            tables: schedule.get_tables().to_vec(),
+            rounds: schedule.get_rounds(),
+            lookahead: schedule.get_lookahead(),
+            _word: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OwnedSchedule;
+    use crate::builder::ScheduleBuilder;
+    use crate::schedule::{Lookahead, ScheduleErrors};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn solves_as_the_borrowed_schedule() {
+        let tables = [3, 3, 3, 3];
+        let lookahead = Lookahead {
+            depth: 1,
+            max_probes: 4,
+        };
+        let borrowed = ScheduleBuilder::new(&tables)
+            .rounds(3)
+            .build()
+            .unwrap()
+            .with_lookahead(lookahead);
+        let owned = OwnedSchedule::<usize>::new(tables.to_vec(), 3)
+            .unwrap()
+            .with_lookahead(lookahead);
+        assert_eq!(OwnedSchedule::from(&borrowed), owned);
+
+        let solve = |schedule: crate::schedule::Schedule<'_>| -> Vec<usize> {
+            let mut buffer = vec![0; schedule.get_search_buffer_size()];
+            let mut search = schedule.fixed_search(&mut buffer).unwrap();
+            let block = search.solve().unwrap();
+            schedule.to_solution(block).unwrap().get_seats().to_vec()
+        };
+        assert_eq!(solve(owned.as_schedule()), solve(borrowed));
+    }
+
+    #[test]
+    fn rejects_what_the_builder_rejects() {
+        let new = |tables: Vec<usize>, rounds| OwnedSchedule::<u64>::new(tables, rounds);
+        assert_eq!(new(vec![], 1), Err(ScheduleErrors::NoTables));
+        assert_eq!(
+            new(vec![3, 0, 3], 1),
+            Err(ScheduleErrors::ZeroLengthGroups { table: 1 })
+        );
+        assert_eq!(new(vec![3, 3], 0), Err(ScheduleErrors::ZeroRounds));
+        assert_eq!(
+            new(vec![3, 3], 3),
+            Err(ScheduleErrors::RoundsTooLarge {
+                rounds: 3,
+                limit: 2
+            })
+        );
+        assert_eq!(
+            new(vec![usize::MAX, 1], 1),
+            Err(ScheduleErrors::PlayerCountOverflow { table: 1 })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn round_trips_through_json() {
+        let owned = OwnedSchedule::<u64>::new(vec![4, 4, 3], 3)
+            .unwrap()
+            .with_lookahead(Lookahead {
+                depth: 1,
+                max_probes: 2,
+            });
+        let json = serde_json::to_string(&owned).unwrap();
+        assert_eq!(
+            json,
+            r#"{"tables":[4,4,3],"rounds":3,"lookahead":{"depth":1,"max_probes":2}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<OwnedSchedule<u64>>(&json).unwrap(),
+            owned
+        );
+
+        let read = |json: &str| serde_json::from_str::<OwnedSchedule<u64>>(json);
+        let lookahead = r#""lookahead":{"depth":0,"max_probes":0}"#;
+        assert!(read(&alloc::format!(
+            r#"{{"tables":[4,4,3],"rounds":4,{}}}"#,
+            lookahead
+        ))
+        .is_err());
+        assert!(read(&alloc::format!(
+            r#"{{"tables":[],"rounds":1,{}}}"#,
+            lookahead
+        ))
+        .is_err());
+        assert!(read(r#"{"tables":[4,4,3],"rounds":3}"#).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_is_checked() {
+        use super::ScheduleConfig;
+        use core::convert::TryFrom;
+
+        let lookahead = Lookahead {
+            depth: 2,
+            max_probes: 8,
+        };
+        let config = |tables: Vec<usize>, rounds| ScheduleConfig {
+            tables,
+            rounds,
+            lookahead,
+        };
+        assert_eq!(
+            OwnedSchedule::<u32>::try_from(config(vec![3, 3], 3)),
+            Err(ScheduleErrors::RoundsTooLarge {
+                rounds: 3,
+                limit: 2
+            })
+        );
+        assert_eq!(
+            OwnedSchedule::<u32>::try_from(config(vec![0], 1)),
+            Err(ScheduleErrors::ZeroLengthGroups { table: 0 })
+        );
+        let owned = OwnedSchedule::<u32>::try_from(config(vec![3, 3], 2)).unwrap();
+        assert_eq!(owned.get_tables(), [3, 3]);
+        assert_eq!(owned.get_rounds(), 2);
+        assert_eq!(owned.get_lookahead(), lookahead);
+    }
+}
@@ -0,0 +1,444 @@
+use crate::bitset::BitSet;
+use crate::feasibility::Infeasibility;
+use crate::schedule::Schedule;
+use crate::solution::Solution;
+use crate::word::for_each_schedule_word;
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RepairError {
+    /// The roster needs an entry for every published player, mapping them to different players
+    /// of the new schedule, or to `None` if they left
+    InvalidRoster,
+    FrozenRoundsTooLarge {
+        frozen_rounds: usize,
+        published_rounds: usize,
+    },
+    /// No schedule for the remaining rounds fits the history, even without keeping any seats
+    Infeasible(Infeasibility),
+}
+
+impl core::fmt::Display for RepairError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidRoster => write!(f, "the roster does not map the published players"),
+            Self::FrozenRoundsTooLarge {
+                frozen_rounds,
+                published_rounds,
+            } => write!(
+                f,
+                "{} rounds are frozen, but only {} were published",
+                frozen_rounds, published_rounds
+            ),
+            Self::Infeasible(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RepairError {}
+
+/// The remaining rounds of a repaired schedule, numbered by the new roster
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Repair {
+    solution: Solution,
+    seat_changes: usize,
+    steps: usize,
+}
+
+impl Repair {
+    pub fn get_solution(&self) -> &Solution {
+        &self.solution
+    }
+
+    pub fn into_solution(self) -> Solution {
+        self.solution
+    }
+
+    /// Published players who sit at a different table than published, counted once per round
+    pub fn get_seat_changes(&self) -> usize {
+        self.seat_changes
+    }
+
+    /// Depth first search steps over every choice of seats to keep
+    pub fn get_steps(&self) -> usize {
+        self.steps
+    }
+}
+
+macro_rules! impl_repair {
+    ($w: ty) => {
+        impl<'a> Schedule<'a, $w> {
+            /// Re-solves the rounds after the first `frozen_rounds` of `published`, once players
+            /// have left or joined, changing as few published seats as possible
+            ///
+            /// `self` covers only the rounds still to be played, with tables for the new roster.
+            /// `roster[player]` is the new number of each published player, or `None` if they
+            /// left, and new players missing from it are latecomers. The frozen rounds are kept as
+            /// history: nobody meets an earlier opponent again or returns to a table with the same
+            /// number.
+            ///
+            /// A branch and bound search decides, for each published seat in the remaining rounds,
+            /// whether the player keeps it or sits elsewhere, trying to keep it first, and
+            /// completes each set of choices with a depth first search. A branch is cut once the
+            /// seats it has changed, and the seats no longer possible, are at least the changes of
+            /// the best repair so far. The result has the fewest seat changes, but the search can
+            /// take exponentially many steps.
+            pub fn repair(
+                &self,
+                published: &Solution,
+                frozen_rounds: usize,
+                roster: &[Option<usize>],
+            ) -> Result<Repair, RepairError> {
+                let published_rounds = published.get_round_count();
+                if frozen_rounds > published_rounds {
+                    return Err(RepairError::FrozenRoundsTooLarge {
+                        frozen_rounds,
+                        published_rounds,
+                    });
+                }
+                if roster.len() != published.get_player_count() {
+                    return Err(RepairError::InvalidRoster);
+                }
+                let mut seen = vec![false; self.get_player_count()];
+                for player in roster.iter().flatten() {
+                    if *player >= seen.len() || core::mem::replace(&mut seen[*player], true) {
+                        return Err(RepairError::InvalidRoster);
+                    }
+                }
+
+                let block_size = self.get_block_size();
+                // One block per decided seat, starting from the history
+                let mut blocks = vec![0; block_size];
+                assert!(
+                    self.initialise_open_buffer(&mut blocks),
+                    "Schedule has no rounds"
+                );
+                let mut players = Vec::new();
+                for round in 0..frozen_rounds {
+                    for table in 0..published.get_tables().len() {
+                        players.clear();
+                        if let Some(seats) = published.players_at(round, table) {
+                            players.extend(seats.iter().filter_map(|player| roster[*player]));
+                        }
+                        self.record_past_table(&mut blocks, table, &players);
+                    }
+                }
+
+                // Published seats of the remaining rounds, as (round, table, player)
+                let compared_rounds = self.get_rounds().min(published_rounds - frozen_rounds);
+                let mut pins = Vec::new();
+                for round in 0..compared_rounds {
+                    for table in 0..published.get_tables().len() {
+                        if let Some(seats) = published.players_at(frozen_rounds + round, table) {
+                            pins.extend(
+                                seats
+                                    .iter()
+                                    .filter_map(|player| roster[*player])
+                                    .map(|player| (round, table, player)),
+                            );
+                        }
+                    }
+                }
+                blocks.resize(block_size * (pins.len() + 1), 0);
+
+                let is_seated = |block: &[$w], (round, table, player): (usize, usize, usize)| {
+                    table < self.get_tables().len()
+                        && BitSet::<$w>::new(
+                            self.get_fixed_words(block, round, table),
+                            self.get_player_count(),
+                        )
+                        .contains(player)
+                };
+                let count_changes = |solution: &Solution| {
+                    let mut changes = 0;
+                    for round in 0..compared_rounds {
+                        for (player, new_player) in roster.iter().enumerate() {
+                            if let Some(new_player) = new_player {
+                                if solution.table_of(*new_player, round)
+                                    != published.table_of(player, frozen_rounds + round)
+                                {
+                                    changes += 1;
+                                }
+                            }
+                        }
+                    }
+                    changes
+                };
+
+                // As `get_max_depth`, with the first round open too
+                let mut search_buffer =
+                    vec![0; block_size * (self.get_player_count() * self.get_rounds() + 2)];
+                let mut best: Option<(Solution, usize)> = None;
+                let mut steps = 0;
+                // For each decided seat, the seats released so far and the next choice to try,
+                // 0 to keep it, 1 to release it and 2 once both are done
+                let mut choices = vec![(0, 0)];
+                while let Some(&(released, choice)) = choices.last() {
+                    let depth = choices.len() - 1;
+                    let (done, rest) = blocks.split_at_mut((depth + 1) * block_size);
+                    let block = &done[depth * block_size..];
+                    let best_changes = best.as_ref().map_or(usize::MAX, |(_, changes)| *changes);
+                    let unreachable = pins[depth..]
+                        .iter()
+                        .filter(|&&(round, table, player)| {
+                            !is_seated(block, (round, table, player))
+                                && !self.can_place_player_at(block, round, table, player)
+                        })
+                        .count();
+                    if choice == 2 || released + unreachable >= best_changes {
+                        choices.pop();
+                        continue;
+                    }
+
+                    if depth == pins.len() {
+                        choices.pop();
+                        search_buffer[..block_size].copy_from_slice(block);
+                        let mut search = self.fixed_search_from(&mut search_buffer);
+                        let solved = search.solve().map(|block| self.to_solution(block));
+                        steps += search.get_steps();
+                        if let Ok(solution) = solved {
+                            let solution = solution.expect("Solved block is incomplete");
+                            let changes = count_changes(&solution);
+                            if changes < best_changes {
+                                best = Some((solution, changes));
+                            }
+                        }
+                        continue;
+                    }
+
+                    let pin = pins[depth];
+                    let next = &mut rest[..block_size];
+                    next.copy_from_slice(block);
+                    let depth_choice = &mut choices[depth].1;
+                    if choice == 0 {
+                        // A seat already taken by propagation costs nothing and is never released
+                        *depth_choice = if is_seated(block, pin) { 2 } else { 1 };
+                        let (round, table, player) = pin;
+                        if is_seated(block, pin)
+                            || (self.can_place_player_at(block, round, table, player)
+                                && self.apply_player_at(next, round, table, player).is_some()
+                                && self.propagate(next, 0))
+                        {
+                            choices.push((released, 0));
+                        }
+                    } else {
+                        *depth_choice = 2;
+                        let (round, table, player) = pin;
+                        self.remove_candidate_at(next, round, table, player);
+                        if self.propagate(next, 0) {
+                            choices.push((released + 1, 0));
+                        }
+                    }
+                }
+
+                match best {
+                    Some((solution, seat_changes)) => Ok(Repair {
+                        solution,
+                        seat_changes,
+                        steps,
+                    }),
+                    None => Err(RepairError::Infeasible(Infeasibility::SearchExhausted {
+                        steps,
+                    })),
+                }
+            }
+        }
+    };
+}
+
+for_each_schedule_word!(impl_repair);
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::ScheduleBuilder;
+    use crate::solution::Solution;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    /// Seats of the remaining rounds as one table per player and round
+    struct Search<'t> {
+        tables: &'t [usize],
+        player_count: usize,
+        met: Vec<Vec<bool>>,
+        visited: Vec<Vec<bool>>,
+        /// The published table of each player in each remaining round, if they have one
+        published: Vec<Vec<Option<usize>>>,
+        best: usize,
+    }
+
+    impl<'t> Search<'t> {
+        /// The fewest seat changes of any valid seating, trying every table for every player
+        fn fewest_changes(
+            &mut self,
+            round: usize,
+            player: usize,
+            seated: &mut Vec<Vec<usize>>,
+            changes: usize,
+        ) {
+            if changes >= self.best {
+                return;
+            }
+            if round == self.published.len() {
+                self.best = changes;
+                return;
+            }
+            if player == self.player_count {
+                let placed = core::mem::replace(seated, vec![Vec::new(); self.tables.len()]);
+                for players in placed.iter() {
+                    for a in players {
+                        for b in players {
+                            self.met[*a][*b] = a != b;
+                        }
+                    }
+                }
+                self.fewest_changes(round + 1, 0, seated, changes);
+                for players in placed.iter() {
+                    for a in players {
+                        for b in players {
+                            self.met[*a][*b] = false;
+                        }
+                    }
+                }
+                *seated = placed;
+                return;
+            }
+            for table in 0..self.tables.len() {
+                if seated[table].len() == self.tables[table]
+                    || self.visited[player][table]
+                    || seated[table].iter().any(|other| self.met[player][*other])
+                {
+                    continue;
+                }
+                let changed = self.published[round][player]
+                    .map_or(0, |published| (published != table) as usize);
+                seated[table].push(player);
+                self.visited[player][table] = true;
+                self.fewest_changes(round, player + 1, seated, changes + changed);
+                self.visited[player][table] = false;
+                seated[table].pop();
+            }
+        }
+    }
+
+    /// Checks `repair` against the fewest seat changes found by trying every seating
+    fn assert_fewest_changes(
+        published: &Solution,
+        frozen_rounds: usize,
+        roster: &[Option<usize>],
+        tables: &[usize],
+    ) {
+        let rounds = published.get_round_count() - frozen_rounds;
+        let remaining = ScheduleBuilder::new(tables).rounds(rounds).build().unwrap();
+        let repair = remaining.repair(published, frozen_rounds, roster);
+
+        let player_count: usize = tables.iter().sum();
+        let mut search = Search {
+            tables,
+            player_count,
+            met: vec![vec![false; player_count]; player_count],
+            visited: vec![vec![false; tables.len()]; player_count],
+            published: vec![vec![None; player_count]; rounds],
+            best: usize::MAX,
+        };
+        for (player, new_player) in roster.iter().enumerate() {
+            if let Some(new_player) = new_player {
+                for round in 0..rounds {
+                    search.published[round][*new_player] =
+                        published.table_of(player, frozen_rounds + round);
+                }
+            }
+        }
+        for round in 0..frozen_rounds {
+            for table in 0..published.get_tables().len() {
+                let players: Vec<_> = published
+                    .players_at(round, table)
+                    .unwrap()
+                    .iter()
+                    .filter_map(|player| roster[*player])
+                    .collect();
+                for a in players.iter() {
+                    if table < tables.len() {
+                        search.visited[*a][table] = true;
+                    }
+                    for b in players.iter() {
+                        search.met[*a][*b] = a != b;
+                    }
+                }
+            }
+        }
+        search.fewest_changes(0, 0, &mut vec![Vec::new(); tables.len()], 0);
+        match repair {
+            Ok(repair) => {
+                assert_history_kept(repair.get_solution(), &search);
+                assert_eq!(
+                    repair.get_seat_changes(),
+                    search.best,
+                    "{:?} after {} rounds",
+                    roster,
+                    frozen_rounds
+                );
+            }
+            Err(error) => assert_eq!(search.best, usize::MAX, "{}", error),
+        }
+    }
+
+    /// Each published player leaving, or a new player joining, after one or two rounds
+    #[test]
+    fn repair_changes_fewest_seats() {
+        let tables = [3; 5];
+        let full = ScheduleBuilder::new(&tables).rounds(3).build().unwrap();
+        let mut buffer = vec![0; full.get_search_buffer_size()];
+        let published = full
+            .to_solution(full.fixed_search(&mut buffer).unwrap().solve().unwrap())
+            .unwrap();
+        let player_count = published.get_player_count();
+
+        for frozen_rounds in 1..3 {
+            for leaving in 0..player_count {
+                let roster: Vec<_> = (0..player_count)
+                    .map(|player| match player {
+                        _ if player < leaving => Some(player),
+                        _ if player > leaving => Some(player - 1),
+                        _ => None,
+                    })
+                    .collect();
+                assert_fewest_changes(&published, frozen_rounds, &roster, &[3, 3, 3, 3, 2]);
+            }
+            let roster: Vec<_> = (0..player_count).map(Some).collect();
+            for joining_table in 0..tables.len() {
+                let mut grown = tables;
+                grown[joining_table] += 1;
+                assert_fewest_changes(&published, frozen_rounds, &roster, &grown);
+            }
+        }
+    }
+
+    fn assert_history_kept(solution: &Solution, search: &Search<'_>) {
+        let mut met = search.met.clone();
+        let mut visited = search.visited.clone();
+        for round in solution.rounds() {
+            for (table, players) in round.tables().enumerate() {
+                for a in players {
+                    assert!(
+                        !visited[*a][table],
+                        "player {} returns to table {}",
+                        a, table
+                    );
+                    visited[*a][table] = true;
+                    for b in players.iter().filter(|b| *b != a) {
+                        assert!(!met[*a][*b], "players {} and {} meet again", a, b);
+                    }
+                }
+            }
+            for players in round.tables() {
+                for a in players {
+                    for b in players {
+                        met[*a][*b] = a != b;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,15 +1,76 @@
+use crate::bitset::{BitSet, BitSetMut};
+use crate::stats::SearchStats;
 use crate::util::*;
+use crate::word::{for_each_schedule_word, Word};
+use core::marker::PhantomData;
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ScheduleErrors {
-    ZeroLengthGroups,
-    PlayerCountOverflow,
-    TooSmallBuffer,
-    RoundsTooLarge,
+    NoTables,
+    ZeroLengthGroups {
+        table: usize,
+    },
+    /// The player count, or the buffer size derived from it, no longer fits in a `usize`
+    PlayerCountOverflow {
+        table: usize,
+    },
+    TooSmallBuffer {
+        needed: usize,
+        len: usize,
+    },
+    ZeroRounds,
+    /// Each player needs a different table in every round
+    RoundsTooLarge {
+        rounds: usize,
+        limit: usize,
+    },
 }
 
-#[derive(Debug)]
-pub enum ScheduleResult {}
+impl core::fmt::Display for ScheduleErrors {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NoTables => write!(f, "there are no tables"),
+            Self::ZeroLengthGroups { table } => write!(f, "table {} has no seats", table),
+            Self::PlayerCountOverflow { table } => {
+                write!(f, "the player count overflows at table {}", table)
+            }
+            Self::TooSmallBuffer { needed, len } => write!(
+                f,
+                "the buffer holds {} words, but {} are needed",
+                len, needed
+            ),
+            Self::ZeroRounds => write!(f, "there are no rounds"),
+            Self::RoundsTooLarge { rounds, limit } => write!(
+                f,
+                "{} rounds is more than the limit of {}, one per table",
+                rounds, limit
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ScheduleErrors {}
+
+/// Failed-literal probing run by `step` on the table it branches on
+///
+/// Each candidate is placed in a scratch block and followed by `depth` passes of propagation.
+/// Candidates which lead to a contradiction are removed before branching.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lookahead {
+    /// Passes of hidden singles and forced tables after each tentative placement
+    pub depth: usize,
+    /// Most candidates probed per step, where 0 disables probing
+    pub max_probes: usize,
+}
+
+impl Lookahead {
+    pub const DISABLED: Self = Self {
+        depth: 0,
+        max_probes: 0,
+    };
+}
 
 #[derive(Debug)]
 struct Offsets {
@@ -62,8 +123,9 @@ impl Offsets {
     }
 }
 
+/// The bit sets of a block are stored in words of type `W`
 #[derive(Debug)]
-pub struct Schedule<'a> {
+pub struct Schedule<'a, W: Word = usize> {
     tables: &'a [usize],
     round_range: RoundRange,
     table_range: TableRange,
@@ -71,9 +133,11 @@ pub struct Schedule<'a> {
     player_count: usize,
     player_bit_word_count: usize,
     offsets: Offsets,
+    lookahead: Lookahead,
+    _word: PhantomData<W>,
 }
 
-impl<'a> Schedule<'a> {
+impl<'a, W: Word> Schedule<'a, W> {
     pub const fn new(tables: &'a [usize], rounds: usize) -> Self {
         let mut player_count: usize = 0;
         let mut i = 0;
@@ -106,13 +170,20 @@ impl<'a> Schedule<'a> {
             rounds,
             player_bit_word_count,
             offsets,
+            lookahead: Lookahead::DISABLED,
+            _word: PhantomData,
         }
     }
 
-    pub fn format_schedule<W: core::fmt::Write>(
+    pub const fn with_lookahead(mut self, lookahead: Lookahead) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
+    pub fn format_schedule<F: core::fmt::Write>(
         &self,
-        buffer: &[usize],
-        output: &mut W,
+        buffer: &[W],
+        output: &mut F,
     ) -> core::fmt::Result {
         fn base_10_length(n: usize) -> usize {
             (1..)
@@ -131,7 +202,7 @@ impl<'a> Schedule<'a> {
             output.write_str("  ")?;
         }
 
-        for round in 0..self.tables.len() {
+        for round in 0..self.rounds {
             output.write_str("\n-----")?;
             for _ in 0..self.tables.len() {
                 output.write_char('+')?;
@@ -151,27 +222,18 @@ impl<'a> Schedule<'a> {
                 }
                 'table: for table in 0..self.tables.len() {
                     output.write_char('|')?;
-                    let mut counter = 0;
-                    for byte in 0..self.player_bit_word_count {
-                        let mut temp = buffer[self.offsets.played_on_table_offset
-                            + self.player_bit_word_count * (round * self.tables.len() + table)
-                            + byte];
-                        while temp != 0 {
-                            let trailing_zeros = temp.trailing_zeros() as usize;
-                            let player = byte * Self::word_size() + trailing_zeros;
-                            let player_bit = 1 << trailing_zeros;
-                            temp &= !player_bit;
-                            if counter == i {
-                                let now = player;
-                                for _ in 0..(3 - base_10_length(now)) {
-                                    output.write_char(' ')?;
-                                }
-                                output.write_fmt(format_args!("{}", now))?;
-                                output.write_str("  ")?;
-                                continue 'table;
-                            }
-                            counter += 1;
+                    let players = self.get_player_bits(
+                        buffer,
+                        self.offsets.played_on_table_offset
+                            + self.player_bit_word_count * (round * self.tables.len() + table),
+                    );
+                    if let Some(now) = players.iter().nth(i) {
+                        for _ in 0..(3 - base_10_length(now)) {
+                            output.write_char(' ')?;
                         }
+                        output.write_fmt(format_args!("{}", now))?;
+                        output.write_str("  ")?;
+                        continue 'table;
                     }
 
                     output.write_str("     ")?;
@@ -181,450 +243,1113 @@ impl<'a> Schedule<'a> {
         Ok(())
     }
 
-    pub fn get_schedule<'b>(&self, buffer: &'b [usize]) -> &'b [usize] {
+    pub fn get_schedule<'b>(&self, buffer: &'b [W]) -> &'b [W] {
         &buffer[self.offsets.played_on_table_offset..][..self.offsets.played_on_table_size]
     }
 
+    pub(crate) fn get_fixed_words<'b>(
+        &self,
+        buffer: &'b [W],
+        round: usize,
+        table: usize,
+    ) -> &'b [W] {
+        &buffer[self.offsets.played_on_table_offset
+            + self.player_bit_word_count * (round * self.tables.len() + table)..]
+            [..self.player_bit_word_count]
+    }
+
+    pub(crate) fn get_potential_words<'b>(
+        &self,
+        buffer: &'b [W],
+        round: usize,
+        table: usize,
+    ) -> &'b [W] {
+        &buffer[self.offsets.potential_on_table_offset
+            + self.player_bit_word_count * (round * self.tables.len() + table)..]
+            [..self.player_bit_word_count]
+    }
+
+    pub(crate) fn get_played_with_words<'b>(&self, buffer: &'b [W], player: usize) -> &'b [W] {
+        &buffer[self.offsets.played_with_offset + self.player_bit_word_count * player..]
+            [..self.player_bit_word_count]
+    }
+
+    pub(crate) fn get_played_on_table_total_words<'b>(
+        &self,
+        buffer: &'b [W],
+        table: usize,
+    ) -> &'b [W] {
+        &buffer[self.offsets.played_on_table_total_offset + self.player_bit_word_count * table..]
+            [..self.player_bit_word_count]
+    }
+
+    pub(crate) fn get_played_in_round_words<'b>(&self, buffer: &'b [W], round: usize) -> &'b [W] {
+        &buffer[self.offsets.played_in_round_offset + self.player_bit_word_count * round..]
+            [..self.player_bit_word_count]
+    }
+
     pub const fn get_block_size(&self) -> usize {
         self.offsets.block_size
     }
 
-    #[must_use]
-    pub const fn initialise_buffer(&self, buffer: &mut [usize]) -> bool {
-        if buffer.len() < self.offsets.block_size {
-            return false;
-        }
-        let mut i = 0;
-        while i < self.offsets.block_size {
-            buffer[i] = 0;
-            i += 1;
-        }
+    pub const fn get_tables(&self) -> &'a [usize] {
+        self.tables
+    }
 
-        let max = Self::get_byte_and_mask(self.player_count);
-        let start =
-            self.offsets.potential_on_table_offset + self.player_bit_word_count * self.tables.len(); // Skip first round
-        let end = self.offsets.potential_on_table_offset + self.offsets.played_on_table_size;
-        let mut i = 0;
-        while start + i < end {
-            let current_byte = i % self.player_bit_word_count;
-            buffer[start + i] = if current_byte > 0 {
-                0
-            } else if current_byte == max.0 {
-                max.1 - 1
-            } else {
-                usize::MAX
-            };
-            i += 1;
-        }
+    pub const fn get_rounds(&self) -> usize {
+        self.rounds
+    }
 
-        buffer[self.offsets.empty_table_count_offset] = (self.rounds - 1) * self.tables.len();
-        let mut round_range = self.round_range.skip(1);
-        while let Some(round) = round_range.next() {
-            let mut table_range = self.table_range;
-            while let Some(table) = table_range.next() {
-                let number = round.as_usize() * self.tables.len() + table.as_usize();
-                let byte = number / Self::word_size();
-                let mask = 1 << (number - (byte * Self::word_size()));
-                buffer[self.offsets.to_explore_offset] |= mask;
-            }
-        }
+    pub const fn get_player_count(&self) -> usize {
+        self.player_count
+    }
 
-        let mut pos = 0;
-        let mut table_range = self.table_range;
-        while let Some(table_number) = table_range.next() {
-            let size = self.tables[table_number.as_usize()];
-            let mut player = pos;
-            let zero = if let Some(zero) = self.round_range.convert_usize(0) {
-                zero
-            } else {
-                return false;
-            };
-            while player < pos + size {
-                self.apply_player(buffer, zero, table_number, player);
-                player += 1;
-            }
-            pos += size;
-        }
-        true
+    pub const fn get_lookahead(&self) -> Lookahead {
+        self.lookahead
     }
 
     const fn word_size() -> usize {
-        core::mem::size_of::<usize>() * 8
+        core::mem::size_of::<W>() * 8
+    }
+
+    /// The player bits starting at `offset` in `buffer`
+    const fn get_player_bits<'b>(&self, buffer: &'b [W], offset: usize) -> BitSet<'b, W> {
+        BitSet::new(buffer.split_at(offset).1, self.player_count)
     }
-    const fn get_byte_and_mask(player: usize) -> (usize, usize) {
-        let byte = player / Self::word_size();
-        let mask = 1 << (player - (byte * Self::word_size()));
-        (byte, mask)
+
+    const fn get_player_bits_mut<'b>(
+        &self,
+        buffer: &'b mut [W],
+        offset: usize,
+    ) -> BitSetMut<'b, W> {
+        BitSetMut::new(buffer.split_at_mut(offset).1, self.player_count)
     }
 
-    const fn apply_player(
+    /// Offset of a table within `played_on_table` or `potential_on_table`
+    const fn get_table_index(&self, round: Round, table: Table) -> usize {
+        self.player_bit_word_count * (round.as_usize() * self.tables.len() + table.as_usize())
+    }
+
+    const fn get_fixed_bits<'b>(
         &self,
-        buffer: &mut [usize],
+        buffer: &'b [W],
         round: Round,
         table: Table,
-        player: usize,
-    ) -> Option<()> {
-        if round.as_usize() >= self.rounds
-            || table.as_usize() >= self.tables.len()
-            || player >= self.player_count
-        {
-            return None;
-        }
-        let (byte, player_mask) = Self::get_byte_and_mask(player);
-        let remove_player_mask = !player_mask;
-        buffer[self.offsets.players_placed_counter_offset] += 1; // Will double count if called multiple times
-        {
-            let mut r2 = 0;
-            while r2 < self.rounds {
-                // Remove player from the table in other rounds
-                buffer[self.offsets.potential_on_table_offset
-                    + self.player_bit_word_count * (r2 * self.tables.len() + table.as_usize())
-                    + byte] &= remove_player_mask;
-                r2 += 1;
-            }
-        }
-        {
-            let mut t2 = 0;
-            while t2 < self.tables.len() {
-                // Remove player from other tables in the same round
-                buffer[self.offsets.potential_on_table_offset
-                    + self.player_bit_word_count * (round.as_usize() * self.tables.len() + t2)
-                    + byte] &= remove_player_mask;
-                t2 += 1;
-            }
-        }
-        // Add player to played in round
-        buffer[self.offsets.played_in_round_offset
-            + self.player_bit_word_count * round.as_usize()
-            + byte] |= player_mask;
-        // Add player to played on table
-        buffer[self.offsets.played_on_table_total_offset
-            + self.player_bit_word_count * table.as_usize()
-            + byte] |= player_mask;
-
-        {
-            let mut other_byte = 0;
-            while other_byte < self.player_bit_word_count {
-                let mut other_players = buffer[self.offsets.played_on_table_offset
-                    + self.player_bit_word_count
-                        * (round.as_usize() * self.tables.len() + table.as_usize())
-                    + other_byte];
+    ) -> BitSet<'b, W> {
+        self.get_player_bits(
+            buffer,
+            self.offsets.played_on_table_offset + self.get_table_index(round, table),
+        )
+    }
 
-                buffer[self.offsets.potential_on_table_offset
-                    + self.player_bit_word_count
-                        * (round.as_usize() * self.tables.len() + table.as_usize())
-                    + other_byte] &= !buffer[self.offsets.played_with_offset
-                    + self.player_bit_word_count * player
-                    + other_byte];
-
-                // Add other players to players played with
-                buffer[self.offsets.played_with_offset
-                    + self.player_bit_word_count * player
-                    + other_byte] |= other_players;
-                while other_players != 0 {
-                    let trailing_zeros = other_players.trailing_zeros() as usize;
-                    let other_player = other_byte * Self::word_size() + trailing_zeros;
-                    let other_player_bit = 1 << trailing_zeros;
-                    other_players &= !other_player_bit;
-
-                    // Add player to other players played with
-                    buffer[self.offsets.played_with_offset
-                        + self.player_bit_word_count * other_player
-                        + byte] |= player_mask;
-                }
+    const fn get_potential_bits<'b>(
+        &self,
+        buffer: &'b [W],
+        round: Round,
+        table: Table,
+    ) -> BitSet<'b, W> {
+        self.get_player_bits(
+            buffer,
+            self.offsets.potential_on_table_offset + self.get_table_index(round, table),
+        )
+    }
 
-                other_byte += 1;
-            }
-        }
+    const fn get_potential_bits_mut<'b>(
+        &self,
+        buffer: &'b mut [W],
+        round: Round,
+        table: Table,
+    ) -> BitSetMut<'b, W> {
+        self.get_player_bits_mut(
+            buffer,
+            self.offsets.potential_on_table_offset + self.get_table_index(round, table),
+        )
+    }
 
-        // Add player to their own table+round
-        buffer[self.offsets.potential_on_table_offset
-            + self.player_bit_word_count
-                * (round.as_usize() * self.tables.len() + table.as_usize())
-            + byte] |= player_mask;
-        buffer[self.offsets.played_on_table_offset
-            + self.player_bit_word_count
-                * (round.as_usize() * self.tables.len() + table.as_usize())
-            + byte] |= player_mask;
-        Some(())
+    const fn get_played_with_bits<'b>(&self, buffer: &'b [W], player: usize) -> BitSet<'b, W> {
+        self.get_player_bits(
+            buffer,
+            self.offsets.played_with_offset + self.player_bit_word_count * player,
+        )
     }
 
-    pub const fn get_players_placed(&self, buffer: &[usize]) -> usize {
-        buffer[self.offsets.players_placed_counter_offset]
+    /// One bit for each table, numbered by `round * tables.len() + table`
+    const fn get_to_explore_bits<'b>(&self, buffer: &'b [W]) -> BitSet<'b, W> {
+        let words = buffer.split_at(self.offsets.to_explore_offset).1;
+        BitSet::new(
+            words.split_at(self.offsets.to_explore_size).0,
+            self.rounds * self.tables.len(),
+        )
     }
 
-    pub const fn get_empty_table_count(&self, buffer: &[usize]) -> usize {
-        buffer[self.offsets.empty_table_count_offset]
+    const fn get_to_explore_bits_mut<'b>(&self, buffer: &'b mut [W]) -> BitSetMut<'b, W> {
+        let words = buffer.split_at_mut(self.offsets.to_explore_offset).1;
+        BitSetMut::new(
+            words.split_at_mut(self.offsets.to_explore_size).0,
+            self.rounds * self.tables.len(),
+        )
     }
+}
 
-    pub const fn find_hidden_singles(&self, buffer: &mut [usize]) {
-        let mut round_range = self.round_range;
-        while let Some(round) = round_range.next() {
-            let mut byte = 0;
-            while byte < self.player_bit_word_count {
-                let mut potential_in_row = !buffer[self.offsets.played_in_round_offset
-                    + self.player_bit_word_count * round.as_usize()
-                    + byte];
-                'loop_bits_round: while potential_in_row != 0 {
-                    let trailing_zeros = potential_in_row.trailing_zeros() as usize;
-                    let player = byte * Self::word_size() + trailing_zeros;
-                    let player_bit = 1 << trailing_zeros;
-                    potential_in_row &= !player_bit;
-                    if player >= self.player_count {
-                        break;
+macro_rules! impl_schedule {
+    ($w: ty) => {
+        impl<'a> Schedule<'a, $w> {
+            pub(crate) const fn is_to_explore(
+                &self,
+                buffer: &[$w],
+                round: usize,
+                table: usize,
+            ) -> bool {
+                let (byte, mask) = Self::get_byte_and_mask(round * self.tables.len() + table);
+                buffer[self.offsets.to_explore_offset + byte] & mask != 0
+            }
+
+            pub const fn try_initialise_buffer(
+                &self,
+                buffer: &mut [$w],
+            ) -> Result<(), ScheduleErrors> {
+                if buffer.len() < self.offsets.block_size {
+                    return Err(ScheduleErrors::TooSmallBuffer {
+                        needed: self.offsets.block_size,
+                        len: buffer.len(),
+                    });
+                }
+                if self.rounds == 0 {
+                    return Err(ScheduleErrors::ZeroRounds);
+                }
+                assert!(self.initialise_buffer(buffer));
+                Ok(())
+            }
+
+            #[must_use]
+            pub const fn initialise_buffer(&self, buffer: &mut [$w]) -> bool {
+                if buffer.len() < self.offsets.block_size || self.rounds == 0 {
+                    return false;
+                }
+                self.open_rounds_from(buffer, 1); // The first round is filled in below
+
+                let mut pos = 0;
+                let mut table_range = self.table_range;
+                while let Some(table_number) = table_range.next() {
+                    let size = self.tables[table_number.as_usize()];
+                    let mut player = pos;
+                    let zero = if let Some(zero) = self.round_range.convert_usize(0) {
+                        zero
+                    } else {
+                        return false;
+                    };
+                    while player < pos + size {
+                        self.apply_player(buffer, zero, table_number, player);
+                        player += 1;
                     }
-                    let mut only_position = None;
+                    pos += size;
+                }
+                true
+            }
+
+            /// Fills `buffer` with every round open and no players placed
+            ///
+            /// Unlike `initialise_buffer` this leaves the first round open, for searches where the
+            /// players are not interchangeable, such as after `record_past_table`.
+            #[cfg(feature = "alloc")]
+            #[must_use]
+            pub(crate) const fn initialise_open_buffer(&self, buffer: &mut [$w]) -> bool {
+                if buffer.len() < self.offsets.block_size || self.rounds == 0 {
+                    return false;
+                }
+                self.open_rounds_from(buffer, 0);
+                true
+            }
+
+            /// Clears the block and makes every player a candidate for every table from
+            /// `first_round` on
+            const fn open_rounds_from(&self, buffer: &mut [$w], first_round: usize) {
+                let mut i = 0;
+                while i < self.offsets.block_size {
+                    buffer[i] = 0;
+                    i += 1;
+                }
+
+                let max = Self::get_byte_and_mask(self.player_count);
+                let start = self.offsets.potential_on_table_offset
+                    + self.player_bit_word_count * self.tables.len() * first_round;
+                let end =
+                    self.offsets.potential_on_table_offset + self.offsets.played_on_table_size;
+                let mut i = 0;
+                while start + i < end {
+                    let current_byte = i % self.player_bit_word_count;
+                    // Every player is a candidate, leaving the bits past the last player clear
+                    buffer[start + i] = if current_byte < max.0 {
+                        <$w>::MAX
+                    } else if current_byte == max.0 {
+                        max.1 - 1
+                    } else {
+                        0
+                    };
+                    i += 1;
+                }
+
+                buffer[self.offsets.empty_table_count_offset] =
+                    ((self.rounds - first_round) * self.tables.len()) as $w;
+                let mut round_range = self.round_range.skip(first_round);
+                while let Some(round) = round_range.next() {
                     let mut table_range = self.table_range;
                     while let Some(table) = table_range.next() {
-                        if buffer[self.offsets.potential_on_table_offset
-                            + self.player_bit_word_count
-                                * (round.as_usize() * self.tables.len() + table.as_usize())
-                            + byte]
-                            & player_bit
-                            != 0
-                        {
-                            if only_position.is_none() {
-                                only_position = Some(table);
-                            } else {
-                                continue 'loop_bits_round;
-                            }
+                        let number = round.as_usize() * self.tables.len() + table.as_usize();
+                        let byte = number / Self::word_size();
+                        let mask = 1 << (number - (byte * Self::word_size()));
+                        buffer[self.offsets.to_explore_offset + byte] |= mask;
+                    }
+                }
+            }
+
+            /// Records `players` as having sat together at `table` in a round before this
+            /// schedule, so they are not seated together again and do not return to `table`
+            #[cfg(feature = "alloc")]
+            pub(crate) const fn record_past_table(
+                &self,
+                buffer: &mut [$w],
+                table: usize,
+                players: &[usize],
+            ) {
+                let mut i = 0;
+                while i < players.len() {
+                    let player = players[i];
+                    let mut j = 0;
+                    while j < players.len() {
+                        if players[j] != player {
+                            self.get_player_bits_mut(
+                                buffer,
+                                self.offsets.played_with_offset
+                                    + self.player_bit_word_count * player,
+                            )
+                            .insert(players[j]);
                         }
+                        j += 1;
                     }
-                    if let Some(table) = only_position {
-                        //println!("Found single location: {:?}", (round, table, player));
-                        self.apply_player(buffer, round, table, player);
+                    if table < self.tables.len() {
+                        self.get_player_bits_mut(
+                            buffer,
+                            self.offsets.played_on_table_total_offset
+                                + self.player_bit_word_count * table,
+                        )
+                        .insert(player);
+                        let mut round = 0;
+                        while round < self.rounds {
+                            self.get_player_bits_mut(
+                                buffer,
+                                self.offsets.potential_on_table_offset
+                                    + self.player_bit_word_count
+                                        * (round * self.tables.len() + table),
+                            )
+                            .remove(player);
+                            round += 1;
+                        }
                     }
+                    i += 1;
                 }
-                byte += 1;
             }
-        }
 
-        let mut table_range = self.table_range;
-        while let Some(table) = table_range.next() {
-            let mut byte = 0;
-            while byte < self.player_bit_word_count {
-                let mut potential_in_column = !buffer[self.offsets.played_on_table_total_offset
-                    + self.player_bit_word_count * table.as_usize()
-                    + byte];
-                'loop_bits_table: while potential_in_column != 0 {
-                    let trailing_zeros = potential_in_column.trailing_zeros() as usize;
-                    let player = byte * Self::word_size() + trailing_zeros;
-                    let player_bit = 1 << trailing_zeros;
-                    potential_in_column &= !player_bit;
-                    if player >= self.player_count {
-                        break;
+            /// Whether `player` is still a candidate for the table, which has a free seat and no
+            /// earlier opponent of theirs
+            #[cfg(feature = "alloc")]
+            pub(crate) const fn can_place_player_at(
+                &self,
+                buffer: &[$w],
+                round: usize,
+                table: usize,
+                player: usize,
+            ) -> bool {
+                let (round, table) = match (
+                    self.round_range.convert_usize(round),
+                    self.table_range.convert_usize(table),
+                ) {
+                    (Some(round), Some(table)) => (round, table),
+                    _ => return false,
+                };
+                player < self.player_count
+                    && self
+                        .get_potential_bits(buffer, round, table)
+                        .contains(player)
+                    && self.get_fixed_count(buffer, round, table)
+                        < self.tables[table.as_usize()] as u32
+                    && self
+                        .get_played_with_bits(buffer, player)
+                        .is_disjoint(&self.get_fixed_bits(buffer, round, table))
+            }
+
+            /// Rules out `player` for the table, if it exists
+            #[cfg(feature = "alloc")]
+            pub(crate) const fn remove_candidate_at(
+                &self,
+                buffer: &mut [$w],
+                round: usize,
+                table: usize,
+                player: usize,
+            ) {
+                if let (Some(round), Some(table)) = (
+                    self.round_range.convert_usize(round),
+                    self.table_range.convert_usize(table),
+                ) {
+                    if player < self.player_count {
+                        self.get_potential_bits_mut(buffer, round, table)
+                            .remove(player);
                     }
-                    let mut only_position = None;
-                    let mut round_range = self.round_range;
-                    while let Some(round) = round_range.next() {
-                        if buffer[self.offsets.potential_on_table_offset
+                }
+            }
+
+            const fn get_byte_and_mask(player: usize) -> (usize, $w) {
+                let byte = player / Self::word_size();
+                let mask = 1 << (player - (byte * Self::word_size()));
+                (byte, mask)
+            }
+
+            const fn apply_player(
+                &self,
+                buffer: &mut [$w],
+                round: Round,
+                table: Table,
+                player: usize,
+            ) -> Option<()> {
+                if round.as_usize() >= self.rounds
+                    || table.as_usize() >= self.tables.len()
+                    || player >= self.player_count
+                {
+                    return None;
+                }
+                let (byte, player_mask) = Self::get_byte_and_mask(player);
+                let remove_player_mask = !player_mask;
+                buffer[self.offsets.players_placed_counter_offset] += 1; // Will double count if called multiple times
+                {
+                    let mut r2 = 0;
+                    while r2 < self.rounds {
+                        // Remove player from the table in other rounds
+                        buffer[self.offsets.potential_on_table_offset
                             + self.player_bit_word_count
-                                * (round.as_usize() * self.tables.len() + table.as_usize())
-                            + byte]
-                            & player_bit
-                            != 0
-                        {
-                            if only_position.is_none() {
-                                only_position = Some(round);
-                            } else {
-                                continue 'loop_bits_table;
-                            }
-                        }
+                                * (r2 * self.tables.len() + table.as_usize())
+                            + byte] &= remove_player_mask;
+                        r2 += 1;
                     }
-                    if let Some(round) = only_position {
-                        //println!("Found single location: {:?}", (round, table, player));
-                        self.apply_player(buffer, round, table, player);
+                }
+                {
+                    let mut t2 = 0;
+                    while t2 < self.tables.len() {
+                        // Remove player from other tables in the same round
+                        buffer[self.offsets.potential_on_table_offset
+                            + self.player_bit_word_count
+                                * (round.as_usize() * self.tables.len() + t2)
+                            + byte] &= remove_player_mask;
+                        t2 += 1;
                     }
                 }
-                byte += 1;
-            }
-        }
-    }
+                // Add player to played in round
+                buffer[self.offsets.played_in_round_offset
+                    + self.player_bit_word_count * round.as_usize()
+                    + byte] |= player_mask;
+                // Add player to played on table
+                buffer[self.offsets.played_on_table_total_offset
+                    + self.player_bit_word_count * table.as_usize()
+                    + byte] |= player_mask;
 
-    const fn get_fixed_count(&self, buffer: &mut [usize], round: Round, table: Table) -> u32 {
-        let mut fixed_player_count = 0;
-        let mut byte = 0;
-        while byte < self.player_bit_word_count {
-            fixed_player_count += buffer[self.offsets.played_on_table_offset
-                + self.player_bit_word_count
-                    * (round.as_usize() * self.tables.len() + table.as_usize())
-                + byte]
-                .count_ones();
-            byte += 1;
-        }
-        fixed_player_count
-    }
-
-    const fn get_potential_count(&self, buffer: &mut [usize], round: Round, table: Table) -> u32 {
-        let mut potential_player_count = 0;
-        let mut byte = 0;
-        while byte < self.player_bit_word_count {
-            potential_player_count += buffer[self.offsets.potential_on_table_offset
-                + self.player_bit_word_count
-                    * (round.as_usize() * self.tables.len() + table.as_usize())
-                + byte]
-                .count_ones();
-            byte += 1;
-        }
-        potential_player_count
-    }
+                {
+                    let mut other_byte = 0;
+                    while other_byte < self.player_bit_word_count {
+                        let other_players = buffer[self.offsets.played_on_table_offset
+                            + self.player_bit_word_count
+                                * (round.as_usize() * self.tables.len() + table.as_usize())
+                            + other_byte];
 
-    const fn can_place_player_on_table(
-        &self,
-        buffer: &mut [usize],
-        round: Round,
-        table: Table,
-        player: usize,
-    ) -> bool {
-        let mut byte = 0;
-        while byte < self.player_bit_word_count {
-            if buffer[self.offsets.played_with_offset + self.player_bit_word_count * player + byte]
-                & buffer[self.offsets.played_on_table_offset
+                        buffer[self.offsets.potential_on_table_offset
+                            + self.player_bit_word_count
+                                * (round.as_usize() * self.tables.len() + table.as_usize())
+                            + other_byte] &= !buffer[self.offsets.played_with_offset
+                            + self.player_bit_word_count * player
+                            + other_byte];
+
+                        // Add other players to players played with
+                        buffer[self.offsets.played_with_offset
+                            + self.player_bit_word_count * player
+                            + other_byte] |= other_players;
+
+                        other_byte += 1;
+                    }
+                    let mut start = 0;
+                    while let Some(other_player) = self
+                        .get_fixed_bits(buffer, round, table)
+                        .first_set_from(start)
+                    {
+                        start = other_player + 1;
+                        // Add player to other players played with
+                        buffer[self.offsets.played_with_offset
+                            + self.player_bit_word_count * other_player
+                            + byte] |= player_mask;
+                    }
+                }
+
+                // Add player to their own table+round
+                buffer[self.offsets.potential_on_table_offset
+                    + self.player_bit_word_count
+                        * (round.as_usize() * self.tables.len() + table.as_usize())
+                    + byte] |= player_mask;
+                buffer[self.offsets.played_on_table_offset
                     + self.player_bit_word_count
                         * (round.as_usize() * self.tables.len() + table.as_usize())
-                    + byte]
-                != 0
-            {
-                return false;
+                    + byte] |= player_mask;
+                Some(())
             }
-            byte += 1;
-        }
-        true
-    }
-
-    pub fn step(&self, buffer_1: &mut [usize], buffer_2: &mut [usize]) -> Option<bool> {
-        let buffer_1 = &mut buffer_1[..self.offsets.block_size];
-        let buffer_2 = &mut buffer_2[..self.offsets.block_size];
-
-        self.find_hidden_singles(buffer_1);
-        let offset = self.offsets.potential_on_table_offset;
-
-        let mut lowest: Option<(u32, Round, Table)> = None;
-        for to_explore_byte in 0..self.offsets.to_explore_size {
-            let mut to_explore = buffer_1[self.offsets.to_explore_offset + to_explore_byte];
-            while to_explore != 0 {
-                let trailing_zeros = to_explore.trailing_zeros();
-                let number = to_explore_byte * Self::word_size() + trailing_zeros as usize;
-                to_explore &= !(1 << trailing_zeros);
-                let (round, table) = if let Some(val) = self
-                    .round_range
-                    .convert_usize(number / self.tables.len())
-                    .zip(self.table_range.convert_usize(number % self.tables.len()))
-                {
-                    val
+
+            pub(crate) const fn apply_player_at(
+                &self,
+                buffer: &mut [$w],
+                round: usize,
+                table: usize,
+                player: usize,
+            ) -> Option<()> {
+                let round = if let Some(round) = self.round_range.convert_usize(round) {
+                    round
+                } else {
+                    return None;
+                };
+                let table = if let Some(table) = self.table_range.convert_usize(table) {
+                    table
                 } else {
-                    buffer_1[self.offsets.to_explore_offset + to_explore_byte] &=
-                        !(1 << trailing_zeros);
-                    // If round or table is out of bounds, then remove
-                    continue;
+                    return None;
                 };
-                let table_size = self.tables[table.as_usize()] as u32;
+                self.apply_player(buffer, round, table, player)
+            }
+
+            /// Removes every full table from `to_explore`, the same way `step` does when it reaches one
+            pub(crate) const fn close_full_tables(&self, buffer: &mut [$w]) {
+                let mut round_range = self.round_range;
+                while let Some(round) = round_range.next() {
+                    let mut table_range = self.table_range;
+                    while let Some(table) = table_range.next() {
+                        let number = round.as_usize() * self.tables.len() + table.as_usize();
+                        let (byte, mask) = Self::get_byte_and_mask(number);
+                        if buffer[self.offsets.to_explore_offset + byte] & mask == 0
+                            || self.get_fixed_count(buffer, round, table)
+                                != self.tables[table.as_usize()] as u32
+                        {
+                            continue;
+                        }
+                        buffer[self.offsets.to_explore_offset + byte] &= !mask;
+                        buffer[self.offsets.empty_table_count_offset] -= 1;
+                        let index = self.player_bit_word_count * number;
+                        let mut byte = 0;
+                        while byte < self.player_bit_word_count {
+                            buffer[self.offsets.potential_on_table_offset + index + byte] =
+                                buffer[self.offsets.played_on_table_offset + index + byte];
+                            byte += 1;
+                        }
+                    }
+                }
+            }
 
-                let fixed_player_count = self.get_fixed_count(buffer_1, round, table);
+            pub const fn get_players_placed(&self, buffer: &[$w]) -> usize {
+                buffer[self.offsets.players_placed_counter_offset] as usize
+            }
 
-                match fixed_player_count.cmp(&table_size) {
-                    core::cmp::Ordering::Less => {
-                        if self.get_potential_count(buffer_1, round, table) == table_size {
-                            let potential_index = self.offsets.potential_on_table_offset
-                                + self.player_bit_word_count
-                                    * (round.as_usize() * self.tables.len() + table.as_usize());
-                            let fixed_index = self.offsets.played_on_table_offset
-                                + self.player_bit_word_count
-                                    * (round.as_usize() * self.tables.len() + table.as_usize());
-                            for byte in 0..self.player_bit_word_count {
-                                loop {
-                                    let potential = buffer_1[potential_index + byte]
-                                        & !buffer_1[fixed_index + byte];
-                                    if potential != 0 {
-                                        let trailing_zeros = potential.trailing_zeros() as usize;
-                                        let player = byte * Self::word_size() + trailing_zeros;
-                                        let player_bit = 1 << trailing_zeros;
-                                        if self.can_place_player_on_table(
-                                            buffer_1, round, table, player,
-                                        ) {
-                                            self.apply_player(buffer_1, round, table, player);
-                                        } else {
-                                            buffer_1[potential_index + byte] &= !player_bit;
-                                        }
-                                    } else {
-                                        break;
-                                    }
+            pub const fn get_empty_table_count(&self, buffer: &[$w]) -> usize {
+                buffer[self.offsets.empty_table_count_offset] as usize
+            }
+
+            pub const fn find_hidden_singles(&self, buffer: &mut [$w]) {
+                let mut round_range = self.round_range;
+                while let Some(round) = round_range.next() {
+                    let mut start = 0;
+                    'loop_bits_round: while let Some(player) = self
+                        .get_player_bits(
+                            buffer,
+                            self.offsets.played_in_round_offset
+                                + self.player_bit_word_count * round.as_usize(),
+                        )
+                        .first_unset_from(start)
+                    {
+                        start = player + 1;
+                        let mut only_position = None;
+                        let mut table_range = self.table_range;
+                        while let Some(table) = table_range.next() {
+                            if self
+                                .get_potential_bits(buffer, round, table)
+                                .contains(player)
+                            {
+                                if only_position.is_none() {
+                                    only_position = Some(table);
+                                } else {
+                                    continue 'loop_bits_round;
                                 }
                             }
-                        } else {
-                            lowest = Some(if let Some(lowest) = lowest {
-                                if fixed_player_count < lowest.0 {
-                                    (fixed_player_count, round, table)
+                        }
+                        if let Some(table) = only_position {
+                            //println!("Found single location: {:?}", (round, table, player));
+                            if self.can_place_player_on_table(buffer, round, table, player) {
+                                self.apply_player(buffer, round, table, player);
+                            } else {
+                                // The only position left is with a previous opponent
+                                self.get_potential_bits_mut(buffer, round, table)
+                                    .remove(player);
+                            }
+                        }
+                    }
+                }
+
+                if self.rounds < self.tables.len() {
+                    // Players only have to visit every table when there is a round for each one
+                    return;
+                }
+
+                let mut table_range = self.table_range;
+                while let Some(table) = table_range.next() {
+                    let mut start = 0;
+                    'loop_bits_table: while let Some(player) = self
+                        .get_player_bits(
+                            buffer,
+                            self.offsets.played_on_table_total_offset
+                                + self.player_bit_word_count * table.as_usize(),
+                        )
+                        .first_unset_from(start)
+                    {
+                        start = player + 1;
+                        let mut only_position = None;
+                        let mut round_range = self.round_range;
+                        while let Some(round) = round_range.next() {
+                            if self
+                                .get_potential_bits(buffer, round, table)
+                                .contains(player)
+                            {
+                                if only_position.is_none() {
+                                    only_position = Some(round);
                                 } else {
-                                    lowest
+                                    continue 'loop_bits_table;
                                 }
+                            }
+                        }
+                        if let Some(round) = only_position {
+                            //println!("Found single location: {:?}", (round, table, player));
+                            if self.can_place_player_on_table(buffer, round, table, player) {
+                                self.apply_player(buffer, round, table, player);
                             } else {
-                                (fixed_player_count, round, table)
-                            });
+                                // The only position left is with a previous opponent
+                                self.get_potential_bits_mut(buffer, round, table)
+                                    .remove(player);
+                            }
                         }
                     }
-                    core::cmp::Ordering::Equal => {
-                        buffer_1[self.offsets.to_explore_offset + to_explore_byte] &=
-                            !(1 << trailing_zeros);
+                }
+            }
 
-                        buffer_1[self.offsets.empty_table_count_offset] -= 1;
-                        for byte in 0..self.player_bit_word_count {
-                            // Set potential to fixed players
-                            buffer_1[self.offsets.potential_on_table_offset
-                                + self.player_bit_word_count
-                                    * (round.as_usize() * self.tables.len() + table.as_usize())
-                                + byte] = buffer_1[self.offsets.played_on_table_offset
+            const fn get_fixed_count(&self, buffer: &[$w], round: Round, table: Table) -> u32 {
+                self.get_fixed_bits(buffer, round, table).count_ones() as u32
+            }
+
+            const fn get_potential_count(&self, buffer: &[$w], round: Round, table: Table) -> u32 {
+                self.get_potential_bits(buffer, round, table).count_ones() as u32
+            }
+
+            const fn can_place_player_on_table(
+                &self,
+                buffer: &mut [$w],
+                round: Round,
+                table: Table,
+                player: usize,
+            ) -> bool {
+                self.get_played_with_bits(buffer, player)
+                    .is_disjoint(&self.get_fixed_bits(buffer, round, table))
+            }
+
+            /// Places every candidate on a table with exactly as many candidates as seats
+            const fn fill_forced_table(&self, buffer: &mut [$w], round: Round, table: Table) {
+                while let Some(player) = self
+                    .get_potential_bits(buffer, round, table)
+                    .first_difference_from(&self.get_fixed_bits(buffer, round, table), 0)
+                {
+                    if self.can_place_player_on_table(buffer, round, table, player) {
+                        self.apply_player(buffer, round, table, player);
+                    } else {
+                        self.get_potential_bits_mut(buffer, round, table)
+                            .remove(player);
+                    }
+                }
+            }
+
+            /// Checks every open table, filling forced ones, and then runs up to `passes` rounds of
+            /// hidden singles, each followed by another check
+            ///
+            /// Returns false if a table has more fixed players, or fewer candidates, than seats
+            pub(crate) const fn propagate(&self, buffer: &mut [$w], passes: usize) -> bool {
+                let mut pass = 0;
+                loop {
+                    let players_placed = buffer[self.offsets.players_placed_counter_offset];
+                    let mut round_range = self.round_range;
+                    while let Some(round) = round_range.next() {
+                        let mut table_range = self.table_range;
+                        while let Some(table) = table_range.next() {
+                            let number = round.as_usize() * self.tables.len() + table.as_usize();
+                            let (byte, mask) = Self::get_byte_and_mask(number);
+                            if buffer[self.offsets.to_explore_offset + byte] & mask == 0 {
+                                continue;
+                            }
+                            let table_size = self.tables[table.as_usize()] as u32;
+                            let fixed_player_count = self.get_fixed_count(buffer, round, table);
+                            let potential_player_count =
+                                self.get_potential_count(buffer, round, table);
+                            if fixed_player_count > table_size
+                                || potential_player_count < table_size
+                            {
+                                return false;
+                            }
+                            if fixed_player_count < table_size
+                                && potential_player_count == table_size
+                            {
+                                self.fill_forced_table(buffer, round, table);
+                            }
+                        }
+                    }
+                    if self.has_opponent_shortfall(buffer) {
+                        return false;
+                    }
+                    if pass >= passes {
+                        return true;
+                    }
+                    self.find_hidden_singles(buffer);
+                    if buffer[self.offsets.players_placed_counter_offset] == players_placed {
+                        return true;
+                    }
+                    pass += 1;
+                }
+            }
+
+            /// Removes the candidates for a table which `propagate` shows lead to a contradiction,
+            /// using `scratch` as a work block, and returns how many were removed
+            const fn probe_table(
+                &self,
+                buffer: &mut [$w],
+                scratch: &mut [$w],
+                round: Round,
+                table: Table,
+            ) -> usize {
+                let mut probes = 0;
+                let mut eliminated = 0;
+                let mut start = 0;
+                while let Some(player) = self
+                    .get_potential_bits(buffer, round, table)
+                    .first_difference_from(&self.get_fixed_bits(buffer, round, table), start)
+                {
+                    if probes >= self.lookahead.max_probes {
+                        return eliminated;
+                    }
+                    start = player + 1;
+                    if !self.can_place_player_on_table(buffer, round, table, player) {
+                        // Removed by `step` without needing a probe
+                        continue;
+                    }
+                    probes += 1;
+                    let mut i = 0;
+                    while i < self.offsets.block_size {
+                        scratch[i] = buffer[i];
+                        i += 1;
+                    }
+                    self.apply_player(scratch, round, table, player);
+                    if !self.propagate(scratch, self.lookahead.depth) {
+                        self.get_potential_bits_mut(buffer, round, table)
+                            .remove(player);
+                        eliminated += 1;
+                    }
+                }
+                eliminated
+            }
+
+            /// Whether a player with k rounds left has fewer than k * (table_size - 1) opponents they have
+            /// not met yet and who are still candidates at one of their open tables, using their
+            /// smallest unvisited table
+            const fn has_opponent_shortfall(&self, buffer: &[$w]) -> bool {
+                let mut player = 0;
+                while player < self.player_count {
+                    let (byte, mask) = Self::get_byte_and_mask(player);
+                    let mut needed = 0;
+                    let mut rounds_left = 0;
+                    let mut round_range = self.round_range;
+                    while let Some(round) = round_range.next() {
+                        if buffer[self.offsets.played_in_round_offset
+                            + self.player_bit_word_count * round.as_usize()
+                            + byte]
+                            & mask
+                            == 0
+                        {
+                            rounds_left += 1;
+                            continue;
+                        }
+                        let mut table_range = self.table_range;
+                        while let Some(table) = table_range.next() {
+                            if buffer[self.offsets.played_on_table_offset
                                 + self.player_bit_word_count
                                     * (round.as_usize() * self.tables.len() + table.as_usize())
                                 + byte]
+                                & mask
+                                != 0
+                            {
+                                // Empty seats at a table they are already on
+                                // A table overfilled by a hidden single is caught by `step`
+                                needed += self.tables[table.as_usize()].saturating_sub(
+                                    self.get_fixed_count(buffer, round, table) as usize,
+                                );
+                            }
                         }
-                        continue;
                     }
-                    core::cmp::Ordering::Greater => return None,
+                    let mut unvisited = 0;
+                    let mut unvisited_opponents = 0;
+                    let mut smallest = usize::MAX;
+                    let mut table = 0;
+                    while table < self.tables.len() {
+                        if buffer[self.offsets.played_on_table_total_offset
+                            + self.player_bit_word_count * table
+                            + byte]
+                            & mask
+                            == 0
+                        {
+                            unvisited += 1;
+                            unvisited_opponents += self.tables[table].saturating_sub(1);
+                            if self.tables[table] < smallest {
+                                smallest = self.tables[table];
+                            }
+                        }
+                        table += 1;
+                    }
+                    if unvisited < rounds_left {
+                        return true;
+                    }
+                    needed += if unvisited == rounds_left {
+                        unvisited_opponents
+                    } else {
+                        rounds_left * smallest.saturating_sub(1)
+                    };
+
+                    let mut available = 0;
+                    let mut other_byte = 0;
+                    while needed > 0 && other_byte < self.player_bit_word_count {
+                        let mut pool = 0;
+                        let to_explore = self.get_to_explore_bits(buffer);
+                        let mut start = 0;
+                        while let Some(number) = to_explore.first_set_from(start) {
+                            start = number + 1;
+                            let index = self.offsets.potential_on_table_offset
+                                + self.player_bit_word_count * number;
+                            if buffer[index + byte] & mask != 0 {
+                                pool |= buffer[index + other_byte];
+                            }
+                        }
+                        pool &= !buffer[self.offsets.played_with_offset
+                            + self.player_bit_word_count * player
+                            + other_byte];
+                        if other_byte == byte {
+                            pool &= !mask;
+                        }
+                        available += pool.count_ones() as usize;
+                        other_byte += 1;
+                    }
+                    if needed > available {
+                        return true;
+                    }
+                    player += 1;
                 }
+                false
             }
-        }
 
-        if let Some((_, round, table)) = lowest {
-            for byte in 0..self.player_bit_word_count {
-                let fixed = buffer_1[self.offsets.played_on_table_offset
-                    + self.player_bit_word_count
-                        * (round.as_usize() * self.tables.len() + table.as_usize())
-                    + byte];
-                let potential = buffer_1[offset
-                    + self.player_bit_word_count
-                        * (round.as_usize() * self.tables.len() + table.as_usize())
-                    + byte]
-                    & !fixed;
-                let mut temp = potential;
-                'played_iter: while temp != 0 {
-                    let trailing_zeros = temp.trailing_zeros() as usize;
-                    let player = byte * Self::word_size() + trailing_zeros;
-                    let player_bit = 1 << trailing_zeros;
-                    temp &= !player_bit;
-                    if !self.can_place_player_on_table(buffer_1, round, table, player) {
-                        // If player has already played with any of the players then remove the player from the potential
-                        buffer_1[offset
-                            + self.player_bit_word_count
-                                * (round.as_usize() * self.tables.len() + table.as_usize())
-                            + byte] &= !player_bit;
-                        continue 'played_iter;
+            pub const fn step(&self, buffer_1: &mut [$w], buffer_2: &mut [$w]) -> Option<bool> {
+                self.step_with_stats(buffer_1, buffer_2, &mut SearchStats::new())
+            }
+
+            pub const fn step_with_stats(
+                &self,
+                buffer_1: &mut [$w],
+                buffer_2: &mut [$w],
+                stats: &mut SearchStats,
+            ) -> Option<bool> {
+                let result = self.step_counting(buffer_1, buffer_2, stats);
+                match result {
+                    Some(false) => stats.nodes += 1,
+                    None => stats.backtracks += 1,
+                    Some(true) => {}
+                }
+                result
+            }
+
+            const fn step_counting(
+                &self,
+                buffer_1: &mut [$w],
+                buffer_2: &mut [$w],
+                stats: &mut SearchStats,
+            ) -> Option<bool> {
+                let buffer_1 = buffer_1.split_at_mut(self.offsets.block_size).0;
+                let buffer_2 = buffer_2.split_at_mut(self.offsets.block_size).0;
+
+                let players_placed = self.get_players_placed(buffer_1);
+                self.find_hidden_singles(buffer_1);
+                stats.hidden_singles += self.get_players_placed(buffer_1) - players_placed;
+                if self.has_opponent_shortfall(buffer_1) {
+                    stats.contradictions.opponent_shortfall += 1;
+                    return None;
+                }
+
+                let mut lowest: Option<(u32, Round, Table)> = None;
+                let mut start = 0;
+                while let Some(number) = self.get_to_explore_bits(buffer_1).first_set_from(start) {
+                    start = number + 1;
+                    let (round, table) = match (
+                        self.round_range.convert_usize(number / self.tables.len()),
+                        self.table_range.convert_usize(number % self.tables.len()),
+                    ) {
+                        (Some(round), Some(table)) => (round, table),
+                        _ => {
+                            // If round or table is out of bounds, then remove
+                            self.get_to_explore_bits_mut(buffer_1).remove(number);
+                            continue;
+                        }
+                    };
+                    let table_size = self.tables[table.as_usize()] as u32;
+
+                    let fixed_player_count = self.get_fixed_count(buffer_1, round, table);
+
+                    if fixed_player_count > table_size {
+                        stats.contradictions.overfull_table += 1;
+                        return None;
+                    } else if fixed_player_count == table_size {
+                        self.get_to_explore_bits_mut(buffer_1).remove(number);
+
+                        buffer_1[self.offsets.empty_table_count_offset] -= 1;
+                        let index = self.get_table_index(round, table);
+                        let mut byte = 0;
+                        while byte < self.player_bit_word_count {
+                            // Set potential to fixed players
+                            buffer_1[self.offsets.potential_on_table_offset + index + byte] =
+                                buffer_1[self.offsets.played_on_table_offset + index + byte];
+                            byte += 1;
+                        }
+                    } else if self.get_potential_count(buffer_1, round, table) == table_size {
+                        self.fill_forced_table(buffer_1, round, table);
+                        stats.forced_tables += 1;
+                    } else {
+                        lowest = Some(if let Some(lowest) = lowest {
+                            if fixed_player_count < lowest.0 {
+                                (fixed_player_count, round, table)
+                            } else {
+                                lowest
+                            }
+                        } else {
+                            (fixed_player_count, round, table)
+                        });
                     }
+                }
 
-                    //buffer_2.copy_from_slice(buffer_1);
-                    for i in 0..self.offsets.block_size {
-                        buffer_2[i] = buffer_1[i];
+                if let Some((_, round, table)) = lowest {
+                    stats.probe_eliminations += self.probe_table(buffer_1, buffer_2, round, table);
+                    let mut start = 0;
+                    while let Some(player) = self
+                        .get_potential_bits(buffer_1, round, table)
+                        .first_difference_from(&self.get_fixed_bits(buffer_1, round, table), start)
+                    {
+                        start = player + 1;
+                        if !self.can_place_player_on_table(buffer_1, round, table, player) {
+                            // If player has already played with any of the players then remove the player from the potential
+                            self.get_potential_bits_mut(buffer_1, round, table)
+                                .remove(player);
+                            continue;
+                        }
+
+                        //buffer_2.copy_from_slice(buffer_1);
+                        let mut i = 0;
+                        while i < self.offsets.block_size {
+                            buffer_2[i] = buffer_1[i];
+                            i += 1;
+                        }
+                        self.get_potential_bits_mut(buffer_1, round, table)
+                            .remove(player);
+                        self.apply_player(buffer_2, round, table, player);
+                        return Some(false);
                     }
-                    buffer_1[offset
-                        + self.player_bit_word_count
-                            * (round.as_usize() * self.tables.len() + table.as_usize())
-                        + byte] &= !player_bit;
-                    self.apply_player(buffer_2, round, table, player);
-                    return Some(false);
+                    // Could not place any player but fixed_player_count < table_size
+                    stats.contradictions.no_candidates += 1;
+                    return None;
                 }
+                // Tables filled by `fill_forced_table` are closed on the next step, unless it had
+                // to remove a candidate and left them short
+                let mut start = 0;
+                while let Some(number) = self.get_to_explore_bits(buffer_1).first_set_from(start) {
+                    start = number + 1;
+                    if let (Some(round), Some(table)) = (
+                        self.round_range.convert_usize(number / self.tables.len()),
+                        self.table_range.convert_usize(number % self.tables.len()),
+                    ) {
+                        if self.get_fixed_count(buffer_1, round, table)
+                            != self.tables[table.as_usize()] as u32
+                        {
+                            stats.contradictions.no_candidates += 1;
+                            return None;
+                        }
+                    }
+                }
+                Some(true)
+            }
+        }
+    };
+}
+
+for_each_schedule_word!(impl_schedule);
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::Lookahead;
+    use crate::builder::ScheduleBuilder;
+    use crate::verify::{verify_seats, Constraints};
+    use alloc::string::String;
+    use alloc::vec;
+
+    macro_rules! assert_solves {
+        ($w: ty, $tables: expr, $rounds: expr) => {{
+            let schedule = ScheduleBuilder::new($tables)
+                .rounds($rounds)
+                .build_with_word::<$w>()
+                .unwrap();
+            let mut buffer = vec![0; schedule.get_search_buffer_size()];
+            let mut search = schedule.fixed_search(&mut buffer).unwrap();
+            let block = search.solve().unwrap();
+            let solution = schedule.to_solution(block).unwrap();
+            let violations = verify_seats($tables, solution.get_seats(), &Constraints::default());
+            assert!(violations.is_empty(), "{:?}", violations);
+        }};
+    }
+
+    /// A forced table whose candidates have met before is left short by `fill_forced_table`,
+    /// which must not be mistaken for a solution
+    #[test]
+    fn step_rejects_forced_table_left_short() {
+        let tables = [2, 2];
+        let schedule = ScheduleBuilder::new(&tables).rounds(1).build().unwrap();
+        let block_size = schedule.get_block_size();
+        let mut buffer = vec![0; block_size * 2];
+        let (block, next) = buffer.split_at_mut(block_size);
+        assert!(schedule.initialise_open_buffer(block));
+        schedule.record_past_table(block, tables.len(), &[0, 1]);
+        schedule.apply_player_at(block, 0, 0, 2).unwrap();
+        schedule.apply_player_at(block, 0, 0, 3).unwrap();
+        assert_eq!(schedule.step(block, next), None);
+    }
+
+    /// A hidden single can overfill a table, which is a contradiction rather than an overflow
+    /// when counting the seats each player still needs to fill
+    #[test]
+    fn step_rejects_table_overfilled_by_hidden_single() {
+        let tables = [1, 1];
+        let schedule = ScheduleBuilder::new(&tables).rounds(1).build().unwrap();
+        let block_size = schedule.get_block_size();
+        let mut buffer = vec![0; block_size * 2];
+        let (block, next) = buffer.split_at_mut(block_size);
+        assert!(schedule.initialise_open_buffer(block));
+        // Player 1 has already sat at the second table, so the first is their only one
+        schedule.record_past_table(block, 1, &[1]);
+        schedule.apply_player_at(block, 0, 0, 0).unwrap();
+        assert_eq!(schedule.step(block, next), None);
+    }
+
+    /// With fewer rounds than tables, a player's one open round is not the only chance to sit
+    /// at each table they have not visited, so it must not be forced
+    #[test]
+    fn solves_fewer_rounds_than_tables() {
+        for (tables, rounds) in [(&[2; 3][..], 2), (&[3; 4][..], 2), (&[3; 4][..], 3)] {
+            assert_solves!(usize, tables, rounds);
+        }
+    }
+
+    /// Probing only removes candidates with no solution, so the search finds the same first
+    /// solution, or none, in fewer steps
+    #[test]
+    fn probing_keeps_the_first_solution() {
+        let probing = Lookahead {
+            depth: 2,
+            max_probes: usize::MAX,
+        };
+        let mut eliminations = 0;
+        for (tables, rounds) in [(&[3; 4][..], 3), (&[3; 5][..], 3), (&[4; 4][..], 4)] {
+            let plain = ScheduleBuilder::new(tables).rounds(rounds).build().unwrap();
+            let probed = ScheduleBuilder::new(tables)
+                .rounds(rounds)
+                .lookahead(probing)
+                .build()
+                .unwrap();
+            let mut plain_buffer = vec![0; plain.get_search_buffer_size()];
+            let mut probed_buffer = vec![0; probed.get_search_buffer_size()];
+            let mut plain_search = plain.fixed_search(&mut plain_buffer).unwrap();
+            let mut probed_search = probed.fixed_search(&mut probed_buffer).unwrap();
+            match (plain_search.solve(), probed_search.solve()) {
+                (Ok(plain_block), Ok(probed_block)) => assert_eq!(
+                    plain.to_solution(plain_block),
+                    probed.to_solution(probed_block),
+                    "{:?}",
+                    tables
+                ),
+                (Err(_), Err(_)) => {}
+                (plain_result, probed_result) => panic!(
+                    "{:?}: {:?} without probing, {:?} with",
+                    tables,
+                    plain_result.map(|_| ()),
+                    probed_result.map(|_| ())
+                ),
             }
-            return None; // Could not place any player but fixed_player_count < table_size
+            eliminations += probed_search.get_stats().probe_eliminations;
+            assert_eq!(plain_search.get_stats().probe_eliminations, 0);
+        }
+        assert!(eliminations > 0);
+    }
+
+    /// One row of tables per round, rather than per table
+    #[test]
+    fn formats_fewer_rounds_than_tables() {
+        let tables = [2; 3];
+        let schedule = ScheduleBuilder::new(&tables).rounds(2).build().unwrap();
+        let mut buffer = vec![0; schedule.get_search_buffer_size()];
+        let mut search = schedule.fixed_search(&mut buffer).unwrap();
+        let block = search.solve().unwrap();
+        let mut output = String::new();
+        schedule.format_schedule(block, &mut output).unwrap();
+        assert_eq!(output.matches("\n-----").count(), 2);
+    }
+
+    /// More tables than one word holds, so `to_explore` spans several words
+    #[test]
+    fn opens_tables_past_the_first_word() {
+        let tables = [2; 12];
+        let schedule = ScheduleBuilder::new(&tables)
+            .rounds(4)
+            .build_with_word::<u32>()
+            .unwrap();
+        let mut buffer = vec![0; schedule.get_block_size()];
+        assert!(schedule.initialise_buffer(&mut buffer));
+        for round in 0..4 {
+            for table in 0..tables.len() {
+                assert_eq!(
+                    schedule.is_to_explore(&buffer, round, table),
+                    round > 0,
+                    "round {} table {}",
+                    round,
+                    table
+                );
+            }
+        }
+    }
+
+    /// Layouts with more players than one word holds, solved with every word type
+    #[test]
+    fn solves_with_every_word() {
+        for (tables, rounds) in [(&[4; 9][..], 2), (&[4; 9][..], 3), (&[3; 12][..], 2)] {
+            assert_solves!(u32, tables, rounds);
+            assert_solves!(u64, tables, rounds);
+            assert_solves!(u128, tables, rounds);
+            assert_solves!(usize, tables, rounds);
         }
-        Some(true)
+        let tables = &[4; 17][..];
+        assert_solves!(u32, tables, 2);
+        assert_solves!(u64, tables, 2);
+        assert_solves!(u128, tables, 2);
+        assert_solves!(usize, tables, 2);
     }
 }
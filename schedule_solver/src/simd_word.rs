@@ -0,0 +1,410 @@
+//! Words of several `u64` lanes, so that word by word loops over player bits run on `core::simd`
+//! vectors
+//!
+//! Bitwise operations work on every lane at once, while shifts and subtraction carry between
+//! lanes so that a `SimdWord` behaves like one wide integer.
+//!
+//! `DFScheduler` can use them, as it is generic over `Word`, but `Schedule` cannot. Its methods
+//! are const fns written once per integer type by `for_each_schedule_word!`, so that a whole
+//! schedule can be solved at compile time, and they use the integers' inherent operations and
+//! `as` casts, since trait methods cannot be called in a const fn. The `Simd` operations are
+//! all trait methods, and a block also stores its counters in words. Supporting `SimdWord`
+//! would mean a second, non-const copy of `Schedule`, or const trait impls once they are
+//! stable.
+use crate::bitset::{BitSet, BitSetMut};
+use crate::word::Word;
+use core::ops::*;
+use core::simd::prelude::*;
+
+/// A word of `LANES * 64` bits, with bit 0 in the lowest bit of lane 0
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct SimdWord<const LANES: usize>(Simd<u64, LANES>);
+
+pub type SimdWord256 = SimdWord<4>;
+pub type SimdWord512 = SimdWord<8>;
+
+macro_rules! impl_simd_word {
+    ($lanes: literal) => {
+        impl SimdWord<$lanes> {
+            pub const fn from_lanes(lanes: [u64; $lanes]) -> Self {
+                Self(Simd::from_array(lanes))
+            }
+
+            pub fn to_lanes(self) -> [u64; $lanes] {
+                self.0.to_array()
+            }
+        }
+
+        impl BitAnd for SimdWord<$lanes> {
+            type Output = Self;
+            #[inline(always)]
+            fn bitand(self, rhs: Self) -> Self {
+                Self(self.0 & rhs.0)
+            }
+        }
+
+        impl BitAndAssign for SimdWord<$lanes> {
+            #[inline(always)]
+            fn bitand_assign(&mut self, rhs: Self) {
+                self.0 &= rhs.0;
+            }
+        }
+
+        impl BitOr for SimdWord<$lanes> {
+            type Output = Self;
+            #[inline(always)]
+            fn bitor(self, rhs: Self) -> Self {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl BitOrAssign for SimdWord<$lanes> {
+            #[inline(always)]
+            fn bitor_assign(&mut self, rhs: Self) {
+                self.0 |= rhs.0;
+            }
+        }
+
+        impl BitXor for SimdWord<$lanes> {
+            type Output = Self;
+            #[inline(always)]
+            fn bitxor(self, rhs: Self) -> Self {
+                Self(self.0 ^ rhs.0)
+            }
+        }
+
+        impl BitXorAssign for SimdWord<$lanes> {
+            #[inline(always)]
+            fn bitxor_assign(&mut self, rhs: Self) {
+                self.0 ^= rhs.0;
+            }
+        }
+
+        impl Not for SimdWord<$lanes> {
+            type Output = Self;
+            #[inline(always)]
+            fn not(self) -> Self {
+                Self(!self.0)
+            }
+        }
+
+        impl Shl<usize> for SimdWord<$lanes> {
+            type Output = Self;
+            fn shl(self, shift: usize) -> Self {
+                let lanes = self.to_lanes();
+                let mut shifted = [0; $lanes];
+                let lane_shift = shift / 64;
+                let bit_shift = shift % 64;
+                for i in lane_shift.min($lanes)..$lanes {
+                    shifted[i] = lanes[i - lane_shift] << bit_shift;
+                    if bit_shift != 0 && i > lane_shift {
+                        shifted[i] |= lanes[i - lane_shift - 1] >> (64 - bit_shift);
+                    }
+                }
+                Self::from_lanes(shifted)
+            }
+        }
+
+        impl Shr<usize> for SimdWord<$lanes> {
+            type Output = Self;
+            fn shr(self, shift: usize) -> Self {
+                let lanes = self.to_lanes();
+                let mut shifted = [0; $lanes];
+                let lane_shift = shift / 64;
+                let bit_shift = shift % 64;
+                for i in 0..$lanes - lane_shift.min($lanes) {
+                    shifted[i] = lanes[i + lane_shift] >> bit_shift;
+                    if bit_shift != 0 && i + lane_shift + 1 < $lanes {
+                        shifted[i] |= lanes[i + lane_shift + 1] << (64 - bit_shift);
+                    }
+                }
+                Self::from_lanes(shifted)
+            }
+        }
+
+        /// Wrapping subtraction, borrowing between lanes
+        impl Sub for SimdWord<$lanes> {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                let lhs = self.to_lanes();
+                let rhs = rhs.to_lanes();
+                let mut difference = [0; $lanes];
+                let mut borrow = false;
+                for i in 0..$lanes {
+                    let (lane, borrow_1) = lhs[i].overflowing_sub(rhs[i]);
+                    let (lane, borrow_2) = lane.overflowing_sub(borrow as u64);
+                    difference[i] = lane;
+                    borrow = borrow_1 || borrow_2;
+                }
+                Self::from_lanes(difference)
+            }
+        }
+
+        impl SubAssign for SimdWord<$lanes> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl core::fmt::Debug for SimdWord<$lanes> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_tuple("SimdWord").field(&self.to_lanes()).finish()
+            }
+        }
+
+        /// The lanes from most to least significant, each as 64 digits
+        impl core::fmt::Binary for SimdWord<$lanes> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                for lane in self.to_lanes().iter().rev() {
+                    write!(f, "{:064b}", lane)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl Word for SimdWord<$lanes> {
+            const ZERO: Self = Self::from_lanes([0; $lanes]);
+            const ONE: Self = Self::from_lanes({
+                let mut lanes = [0; $lanes];
+                lanes[0] = 1;
+                lanes
+            });
+            const MAX: Self = Self::from_lanes([u64::MAX; $lanes]);
+            #[inline(always)]
+            fn count_ones(self) -> u32 {
+                self.0.count_ones().reduce_sum() as u32
+            }
+            #[inline(always)]
+            fn count_zeros(self) -> u32 {
+                self.0.count_zeros().reduce_sum() as u32
+            }
+            fn leading_ones(self) -> u32 {
+                (!self).leading_zeros()
+            }
+            fn leading_zeros(self) -> u32 {
+                let mut zeros = 0;
+                for lane in self.to_lanes().iter().rev() {
+                    zeros += lane.leading_zeros();
+                    if *lane != 0 {
+                        break;
+                    }
+                }
+                zeros
+            }
+            fn trailing_ones(self) -> u32 {
+                (!self).trailing_zeros()
+            }
+            fn trailing_zeros(self) -> u32 {
+                let mut zeros = 0;
+                for lane in self.to_lanes().iter() {
+                    zeros += lane.trailing_zeros();
+                    if *lane != 0 {
+                        break;
+                    }
+                }
+                zeros
+            }
+            fn write_le_bytes(self, bytes: &mut [u8]) {
+                for (lane, chunk) in self.to_lanes().iter().zip(bytes.chunks_exact_mut(8)) {
+                    chunk.copy_from_slice(&lane.to_le_bytes());
+                }
+            }
+            fn read_le_bytes(bytes: &[u8]) -> Self {
+                let mut lanes = [0; $lanes];
+                for (lane, chunk) in lanes.iter_mut().zip(bytes.chunks_exact(8)) {
+                    let mut lane_bytes = [0; 8];
+                    lane_bytes.copy_from_slice(chunk);
+                    *lane = u64::from_le_bytes(lane_bytes);
+                }
+                Self::from_lanes(lanes)
+            }
+
+            fn count_ones_in(set: BitSet<'_, Self>) -> usize {
+                let words = set.get_words();
+                let remainder = set.get_len() % Self::SIZE;
+                let full_words = if remainder == 0 {
+                    words.len()
+                } else {
+                    words.len() - 1
+                };
+                let mut count: usize = words[..full_words]
+                    .iter()
+                    .map(|word| word.count_ones() as usize)
+                    .sum();
+                if remainder != 0 {
+                    let lanes = words[full_words].to_lanes();
+                    for (i, lane) in lanes.iter().enumerate().take(remainder.div_ceil(64)) {
+                        let bits = (remainder - i * 64).min(64);
+                        let mask = if bits == 64 {
+                            u64::MAX
+                        } else {
+                            (1 << bits) - 1
+                        };
+                        count += (lane & mask).count_ones() as usize;
+                    }
+                }
+                count
+            }
+            fn contains_in(set: BitSet<'_, Self>, bit: usize) -> bool {
+                bit < set.get_len()
+                    && (set.get_words()[bit / Self::SIZE].to_lanes()[bit % Self::SIZE / 64]
+                        >> (bit % 64))
+                        & 1
+                        != 0
+            }
+            fn first_set_in(set: BitSet<'_, Self>, start: usize) -> Option<usize> {
+                let words = set.get_words();
+                let mut bit = start;
+                while bit < set.get_len() {
+                    let word = words[bit / Self::SIZE];
+                    if word == Self::ZERO {
+                        bit = (bit / Self::SIZE + 1) * Self::SIZE;
+                        continue;
+                    }
+                    let lane = word.to_lanes()[bit % Self::SIZE / 64] >> (bit % 64);
+                    if lane == 0 {
+                        bit = (bit / 64 + 1) * 64;
+                        continue;
+                    }
+                    bit += lane.trailing_zeros() as usize;
+                    return if bit < set.get_len() { Some(bit) } else { None };
+                }
+                None
+            }
+            fn insert_in(set: &mut BitSetMut<'_, Self>, bit: usize) {
+                assert!(bit < set.get_len());
+                let word = &mut set.get_words_mut()[bit / Self::SIZE];
+                let mut lanes = word.to_lanes();
+                lanes[bit % Self::SIZE / 64] |= 1 << (bit % 64);
+                *word = Self::from_lanes(lanes);
+            }
+            fn remove_in(set: &mut BitSetMut<'_, Self>, bit: usize) {
+                assert!(bit < set.get_len());
+                let word = &mut set.get_words_mut()[bit / Self::SIZE];
+                let mut lanes = word.to_lanes();
+                lanes[bit % Self::SIZE / 64] &= !(1 << (bit % 64));
+                *word = Self::from_lanes(lanes);
+            }
+        }
+    };
+}
+
+impl_simd_word!(4);
+impl_simd_word!(8);
+
+#[cfg(test)]
+mod tests {
+    use super::SimdWord256;
+    use crate::bitset::{BitSet, BitSetMut};
+    use crate::word::Word;
+
+    /// Bits either side of each lane boundary
+    const EDGES: [usize; 9] = [0, 1, 63, 64, 127, 128, 191, 192, 255];
+
+    fn from_bits(bits: &[usize]) -> SimdWord256 {
+        let mut lanes = [0; 4];
+        for bit in bits {
+            lanes[bit / 64] |= 1 << (bit % 64);
+        }
+        SimdWord256::from_lanes(lanes)
+    }
+
+    fn to_bools(word: SimdWord256) -> [bool; 256] {
+        let lanes = word.to_lanes();
+        let mut bools = [false; 256];
+        for (bit, is_set) in bools.iter_mut().enumerate() {
+            *is_set = lanes[bit / 64] >> (bit % 64) & 1 != 0;
+        }
+        bools
+    }
+
+    /// The word as two `u128` halves, low half first
+    fn to_halves(word: SimdWord256) -> (u128, u128) {
+        let lanes = word.to_lanes();
+        (
+            lanes[0] as u128 | (lanes[1] as u128) << 64,
+            lanes[2] as u128 | (lanes[3] as u128) << 64,
+        )
+    }
+
+    #[test]
+    fn shifts_carry_between_lanes() {
+        for shift in [0, 1, 63, 64, 65, 127, 128, 129, 255, 256, 300] {
+            for bit in EDGES {
+                let word = from_bits(&[bit]);
+                let mut expected = [false; 256];
+                if bit + shift < 256 {
+                    expected[bit + shift] = true;
+                }
+                assert_eq!(to_bools(word << shift), expected, "{} << {}", bit, shift);
+                let mut expected = [false; 256];
+                if bit >= shift {
+                    expected[bit - shift] = true;
+                }
+                assert_eq!(to_bools(word >> shift), expected, "{} >> {}", bit, shift);
+            }
+        }
+    }
+
+    #[test]
+    fn subtraction_borrows_between_lanes() {
+        for lhs in EDGES {
+            for rhs in EDGES {
+                let difference = to_halves(from_bits(&[lhs]) - from_bits(&[rhs]));
+                let (lhs_low, lhs_high) = to_halves(from_bits(&[lhs]));
+                let (rhs_low, rhs_high) = to_halves(from_bits(&[rhs]));
+                let (low, borrow) = lhs_low.overflowing_sub(rhs_low);
+                let high = lhs_high.wrapping_sub(rhs_high).wrapping_sub(borrow as u128);
+                assert_eq!(difference, (low, high), "{} - {}", lhs, rhs);
+            }
+        }
+        assert_eq!(SimdWord256::ZERO - SimdWord256::ONE, SimdWord256::MAX);
+    }
+
+    #[test]
+    fn zero_counts_span_lanes() {
+        for bit in EDGES {
+            let word = from_bits(&[bit]);
+            assert_eq!(word.trailing_zeros(), bit as u32);
+            assert_eq!(word.leading_zeros(), 255 - bit as u32);
+            assert_eq!((!word).trailing_ones(), bit as u32);
+            assert_eq!(word.count_ones(), 1);
+        }
+        assert_eq!(SimdWord256::ZERO.trailing_zeros(), 256);
+    }
+
+    #[test]
+    fn sets_match_a_bool_array() {
+        const LEN: usize = 400;
+        let mut words = [SimdWord256::ZERO; 2];
+        let mut expected = [false; LEN];
+        let mut set = BitSetMut::new(&mut words, LEN);
+        for bit in [0, 63, 64, 127, 128, 255, 256, 319, 320, 399] {
+            Word::insert_in(&mut set, bit);
+            expected[bit] = true;
+        }
+        for bit in [64, 256] {
+            Word::remove_in(&mut set, bit);
+            expected[bit] = false;
+        }
+        // Bits past the end of the set are ignored
+        words[1] |= from_bits(&[LEN - 256, 255]);
+
+        let set = BitSet::new(&words, LEN);
+        assert_eq!(
+            SimdWord256::count_ones_in(set),
+            expected.iter().filter(|bit| **bit).count()
+        );
+        for start in 0..=LEN {
+            assert_eq!(
+                SimdWord256::first_set_in(set, start),
+                (start..LEN).find(|bit| expected[*bit]),
+                "from {}",
+                start
+            );
+        }
+        for (bit, is_set) in expected.iter().enumerate() {
+            assert_eq!(SimdWord256::contains_in(set, bit), *is_set, "bit {}", bit);
+        }
+    }
+}
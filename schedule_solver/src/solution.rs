@@ -0,0 +1,280 @@
+use crate::bitset::BitSet;
+use crate::schedule::Schedule;
+use crate::word::{for_each_schedule_word, Word};
+use alloc::vec::Vec;
+
+/// A complete schedule, independent of the buffers used to find it
+///
+/// With the `serde` feature it is stored as its `tables` and `seats`, which are checked again
+/// when read.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "SolutionSeats", try_from = "SolutionSeats")
+)]
+pub struct Solution {
+    tables: Vec<usize>,
+    /// Players in order of round, then table, as used by `DFScheduler`
+    seats: Vec<usize>,
+    /// Start of each table within a round's seats, with the player count at the end
+    table_offsets: Vec<usize>,
+    /// Table of each player, indexed by `round * player_count + player`
+    table_of: Vec<usize>,
+}
+
+/// The stored form of a `Solution`
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SolutionSeats {
+    tables: Vec<usize>,
+    seats: Vec<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Solution> for SolutionSeats {
+    fn from(solution: Solution) -> Self {
+        Self {
+            tables: solution.tables,
+            seats: solution.seats,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl core::convert::TryFrom<SolutionSeats> for Solution {
+    type Error = &'static str;
+
+    fn try_from(stored: SolutionSeats) -> Result<Self, Self::Error> {
+        Solution::from_seats(&stored.tables, &stored.seats)
+            .ok_or("seats are not whole rounds which seat every player once")
+    }
+}
+
+impl Solution {
+    /// Takes whole rounds of players, in order of table, returning `None` if `seats` is not a
+    /// whole number of rounds or a round does not seat every player exactly once
+    pub fn from_seats(tables: &[usize], seats: &[usize]) -> Option<Self> {
+        let mut table_offsets = Vec::with_capacity(tables.len() + 1);
+        let mut player_count = 0;
+        for size in tables.iter() {
+            table_offsets.push(player_count);
+            player_count += size;
+        }
+        table_offsets.push(player_count);
+        if player_count == 0 || !seats.len().is_multiple_of(player_count) {
+            return None;
+        }
+        let mut table_of = alloc::vec![usize::MAX; seats.len()];
+        for (round, round_seats) in seats.chunks(player_count).enumerate() {
+            for (table, window) in table_offsets.windows(2).enumerate() {
+                for player in round_seats[window[0]..window[1]].iter() {
+                    if *player >= player_count
+                        || table_of[round * player_count + player] != usize::MAX
+                    {
+                        return None;
+                    }
+                    table_of[round * player_count + player] = table;
+                }
+            }
+        }
+        Some(Self {
+            tables: tables.to_vec(),
+            seats: seats.to_vec(),
+            table_offsets,
+            table_of,
+        })
+    }
+
+    pub fn get_tables(&self) -> &[usize] {
+        &self.tables
+    }
+
+    pub fn get_seats(&self) -> &[usize] {
+        &self.seats
+    }
+
+    pub fn get_player_count(&self) -> usize {
+        self.table_offsets[self.tables.len()]
+    }
+
+    pub fn get_round_count(&self) -> usize {
+        self.seats.len() / self.get_player_count()
+    }
+
+    pub fn table_of(&self, player: usize, round: usize) -> Option<usize> {
+        if player >= self.get_player_count() {
+            return None;
+        }
+        self.table_of
+            .get(round * self.get_player_count() + player)
+            .copied()
+    }
+
+    pub fn players_at(&self, round: usize, table: usize) -> Option<&[usize]> {
+        if round >= self.get_round_count() || table >= self.tables.len() {
+            return None;
+        }
+        let round_offset = round * self.get_player_count();
+        Some(
+            &self.seats[round_offset + self.table_offsets[table]
+                ..round_offset + self.table_offsets[table + 1]],
+        )
+    }
+
+    /// Rounds in which `player_1` and `player_2` sat at the same table
+    pub fn rounds_met(&self, player_1: usize, player_2: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.get_round_count()).filter(move |round| {
+            player_1 != player_2
+                && self.table_of(player_1, *round).is_some()
+                && self.table_of(player_1, *round) == self.table_of(player_2, *round)
+        })
+    }
+
+    pub fn rounds(&self) -> impl Iterator<Item = SolutionRound<'_>> + '_ {
+        (0..self.get_round_count()).map(move |round| SolutionRound {
+            solution: self,
+            round,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SolutionRound<'a> {
+    solution: &'a Solution,
+    round: usize,
+}
+
+impl<'a> SolutionRound<'a> {
+    pub fn get_round(&self) -> usize {
+        self.round
+    }
+
+    /// Players at each table, in order of table
+    pub fn tables(&self) -> impl Iterator<Item = &'a [usize]> + 'a {
+        let solution = self.solution;
+        let round = self.round;
+        (0..solution.tables.len()).filter_map(move |table| solution.players_at(round, table))
+    }
+}
+
+impl<'a, W: Word> Schedule<'a, W> {
+    /// Reads a complete block, returning `None` if any table is not full
+    pub fn to_solution(&self, buffer: &[W]) -> Option<Solution> {
+        let tables = self.get_tables();
+        let schedule = self.get_schedule(buffer);
+        let player_bit_word_count = schedule.len() / (self.get_rounds() * tables.len());
+        let mut seats = Vec::with_capacity(self.get_rounds() * self.get_player_count());
+        for (number, words) in schedule.chunks(player_bit_word_count).enumerate() {
+            let start = seats.len();
+            seats.extend(BitSet::new(words, self.get_player_count()).iter());
+            if seats.len() - start != tables[number % tables.len()] {
+                return None;
+            }
+        }
+        Solution::from_seats(tables, &seats)
+    }
+
+    /// Reads a block as a flat list of seats, in the format of `Solution::from_seats` and
+    /// `DFScheduler`
+    ///
+    /// The seats stop after the first table which is not full, whose players are included in
+    /// increasing order.
+    pub fn to_seats(&self, buffer: &[W]) -> Vec<usize> {
+        let tables = self.get_tables();
+        let schedule = self.get_schedule(buffer);
+        let player_bit_word_count = schedule.len() / (self.get_rounds() * tables.len());
+        let mut seats = Vec::with_capacity(self.get_rounds() * self.get_player_count());
+        for (number, words) in schedule.chunks(player_bit_word_count).enumerate() {
+            let start = seats.len();
+            seats.extend(BitSet::new(words, self.get_player_count()).iter());
+            if seats.len() - start != tables[number % tables.len()] {
+                break;
+            }
+        }
+        seats
+    }
+}
+
+macro_rules! impl_from_seats {
+    ($w: ty) => {
+        impl<'a> Schedule<'a, $w> {
+            /// Fills `buffer` with the seats of a flat list, as returned by `to_seats`, leaving
+            /// the rest of the schedule open
+            ///
+            /// Unlike `initialise_buffer`, the first round is not fixed. Returns false if a player
+            /// is seated twice in a round, meets an opponent again or returns to a table.
+            #[must_use]
+            pub fn from_seats(&self, seats: &[usize], buffer: &mut [$w]) -> bool {
+                if seats.len() > self.get_rounds() * self.get_player_count()
+                    || !self.initialise_open_buffer(buffer)
+                {
+                    return false;
+                }
+                let mut table_ends = Vec::with_capacity(self.get_tables().len());
+                let mut player_count = 0;
+                for size in self.get_tables().iter() {
+                    player_count += size;
+                    table_ends.push(player_count);
+                }
+                for (seat, player) in seats.iter().enumerate() {
+                    let round = seat / player_count;
+                    let table = table_ends
+                        .iter()
+                        .position(|end| seat % player_count < *end)
+                        .unwrap_or(0);
+                    if !self.can_place_player_at(buffer, round, table, *player) {
+                        return false;
+                    }
+                    self.apply_player_at(buffer, round, table, *player);
+                }
+                self.close_full_tables(buffer);
+                true
+            }
+        }
+    };
+}
+
+for_each_schedule_word!(impl_from_seats);
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::Solution;
+    use crate::builder::ScheduleBuilder;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn round_trips_through_json() {
+        let tables = [3; 4];
+        let schedule = ScheduleBuilder::new(&tables).rounds(3).build().unwrap();
+        let mut buffer = vec![0; schedule.get_search_buffer_size()];
+        let mut search = schedule.fixed_search(&mut buffer).unwrap();
+        let solution = schedule.to_solution(search.solve().unwrap()).unwrap();
+
+        let json = serde_json::to_value(&solution).unwrap();
+        let fields: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(|key| key.as_str())
+            .collect();
+        assert_eq!(fields, ["seats", "tables"]);
+        let read: Solution = serde_json::from_value(json).unwrap();
+        assert_eq!(read, solution);
+        assert_eq!(read.table_of(5, 2), solution.table_of(5, 2));
+    }
+
+    #[test]
+    fn rejects_inconsistent_seats() {
+        let read = |json: &str| serde_json::from_str::<Solution>(json);
+        assert!(read(r#"{"tables":[2,2],"seats":[1,3,0,2,0,1,2,3]}"#).is_ok());
+        // A player seated twice in a round
+        assert!(read(r#"{"tables":[2,2],"seats":[1,3,0,2,0,1,2,2]}"#).is_err());
+        // A partial round
+        assert!(read(r#"{"tables":[2,2],"seats":[1,3,0,2,0,1]}"#).is_err());
+        // A player who does not exist
+        assert!(read(r#"{"tables":[2,2],"seats":[1,3,0,4]}"#).is_err());
+        assert!(read(r#"{"tables":[2,2]}"#).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use core::time::Duration;
+
+/// Dead ends found while searching, by cause
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Contradictions {
+    /// A table had more fixed players than seats
+    pub overfull_table: usize,
+    /// No remaining candidate could be placed at a table with empty seats
+    pub no_candidates: usize,
+    /// A player could no longer meet enough new opponents
+    pub opponent_shortfall: usize,
+}
+
+impl Contradictions {
+    pub const fn new() -> Self {
+        Self {
+            overfull_table: 0,
+            no_candidates: 0,
+            opponent_shortfall: 0,
+        }
+    }
+
+    pub const fn total(&self) -> usize {
+        self.overfull_table + self.no_candidates + self.opponent_shortfall
+    }
+}
+
+/// Search effort, updated by `Schedule::step_with_stats` and the solvers built on it
+///
+/// `max_depth` and `elapsed` are kept by whichever driver owns the search, as a single step has
+/// no idea of either.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchStats {
+    /// Steps which placed a player and went deeper
+    pub nodes: usize,
+    pub backtracks: usize,
+    /// Players placed by `find_hidden_singles`
+    pub hidden_singles: usize,
+    /// Tables filled because they had exactly as many candidates as seats
+    pub forced_tables: usize,
+    /// Candidates removed by lookahead probing
+    pub probe_eliminations: usize,
+    pub contradictions: Contradictions,
+    pub max_depth: usize,
+    pub elapsed: Duration,
+}
+
+impl SearchStats {
+    /// All zero, as `default` but usable in const code
+    pub const fn new() -> Self {
+        Self {
+            nodes: 0,
+            backtracks: 0,
+            hidden_singles: 0,
+            forced_tables: 0,
+            probe_eliminations: 0,
+            contradictions: Contradictions::new(),
+            max_depth: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
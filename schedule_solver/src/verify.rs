@@ -0,0 +1,347 @@
+//! A check of finished schedules which shares no code with the solvers, so that a bug in their
+//! bookkeeping cannot hide itself
+use alloc::vec::Vec;
+
+/// Pairs of players which must not, or must at least once, share a table
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Constraints {
+    pub forbidden_pairs: Vec<(usize, usize)>,
+    pub required_pairs: Vec<(usize, usize)>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Violation {
+    TableCount {
+        round: usize,
+        expected: usize,
+        found: usize,
+    },
+    TableSize {
+        round: usize,
+        table: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A player number of at least the player count
+    UnknownPlayer {
+        round: usize,
+        table: usize,
+        player: usize,
+    },
+    DuplicateSeat {
+        round: usize,
+        player: usize,
+    },
+    MissingPlayer {
+        round: usize,
+        player: usize,
+    },
+    /// Two players shared a table in `first_round` and again in `round`
+    RepeatedPair {
+        players: (usize, usize),
+        first_round: usize,
+        round: usize,
+    },
+    /// A player sat at `table` in `first_round` and again in `round`
+    RepeatedTable {
+        player: usize,
+        table: usize,
+        first_round: usize,
+        round: usize,
+    },
+    ForbiddenPair {
+        players: (usize, usize),
+        round: usize,
+    },
+    RequiredPairNeverMet {
+        players: (usize, usize),
+    },
+}
+
+impl core::fmt::Display for Violation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TableCount {
+                round,
+                expected,
+                found,
+            } => write!(
+                f,
+                "round {} has {} tables, expected {}",
+                round, found, expected
+            ),
+            Self::TableSize {
+                round,
+                table,
+                expected,
+                found,
+            } => write!(
+                f,
+                "round {} table {} has {} players, expected {}",
+                round, table, found, expected
+            ),
+            Self::UnknownPlayer {
+                round,
+                table,
+                player,
+            } => write!(
+                f,
+                "round {} table {} has unknown player {}",
+                round, table, player
+            ),
+            Self::DuplicateSeat { round, player } => {
+                write!(f, "player {} is seated twice in round {}", player, round)
+            }
+            Self::MissingPlayer { round, player } => {
+                write!(f, "player {} is not seated in round {}", player, round)
+            }
+            Self::RepeatedPair {
+                players,
+                first_round,
+                round,
+            } => write!(
+                f,
+                "players {} and {} meet in round {} and again in round {}",
+                players.0, players.1, first_round, round
+            ),
+            Self::RepeatedTable {
+                player,
+                table,
+                first_round,
+                round,
+            } => write!(
+                f,
+                "player {} sits at table {} in round {} and again in round {}",
+                player, table, first_round, round
+            ),
+            Self::ForbiddenPair { players, round } => write!(
+                f,
+                "forbidden pair {} and {} meet in round {}",
+                players.0, players.1, round
+            ),
+            Self::RequiredPairNeverMet { players } => write!(
+                f,
+                "required pair {} and {} never meet",
+                players.0, players.1
+            ),
+        }
+    }
+}
+
+/// Checks a schedule given as the players at each table of each round, returning every
+/// violation found in order of round
+///
+/// Players are numbered from 0 up to the sum of `tables`.
+pub fn verify<R, T>(tables: &[usize], rounds: R, constraints: &Constraints) -> Vec<Violation>
+where
+    R: IntoIterator,
+    R::Item: IntoIterator<Item = T>,
+    T: AsRef<[usize]>,
+{
+    let player_count: usize = tables.iter().sum();
+    let mut violations = Vec::new();
+    // The first round each pair met, indexed by `lower * player_count + higher`
+    let mut pair_met = alloc::vec![None; player_count * player_count];
+    // The first round each player sat at each table, indexed by `player * tables.len() + table`
+    let mut table_visited = alloc::vec![None; player_count * tables.len()];
+    let mut seated = alloc::vec![false; player_count];
+
+    for (round, round_tables) in rounds.into_iter().enumerate() {
+        seated.iter_mut().for_each(|seat| *seat = false);
+        let mut table_count = 0;
+        for (table, players) in round_tables.into_iter().enumerate() {
+            let players = players.as_ref();
+            table_count += 1;
+            if let Some(expected) = tables.get(table) {
+                if players.len() != *expected {
+                    violations.push(Violation::TableSize {
+                        round,
+                        table,
+                        expected: *expected,
+                        found: players.len(),
+                    });
+                }
+            }
+            for (i, player) in players.iter().enumerate() {
+                if *player >= player_count {
+                    violations.push(Violation::UnknownPlayer {
+                        round,
+                        table,
+                        player: *player,
+                    });
+                    continue;
+                }
+                if seated[*player] {
+                    violations.push(Violation::DuplicateSeat {
+                        round,
+                        player: *player,
+                    });
+                }
+                seated[*player] = true;
+
+                if table < tables.len() {
+                    match table_visited[player * tables.len() + table] {
+                        Some(first_round) => violations.push(Violation::RepeatedTable {
+                            player: *player,
+                            table,
+                            first_round,
+                            round,
+                        }),
+                        None => table_visited[player * tables.len() + table] = Some(round),
+                    }
+                }
+
+                for other in players[..i].iter() {
+                    if *other >= player_count || other == player {
+                        continue;
+                    }
+                    let pair = (*other.min(player), *other.max(player));
+                    if constraints.forbidden_pairs.iter().any(|forbidden| {
+                        (forbidden.0.min(forbidden.1), forbidden.0.max(forbidden.1)) == pair
+                    }) {
+                        violations.push(Violation::ForbiddenPair {
+                            players: pair,
+                            round,
+                        });
+                    }
+                    match pair_met[pair.0 * player_count + pair.1] {
+                        Some(first_round) => violations.push(Violation::RepeatedPair {
+                            players: pair,
+                            first_round,
+                            round,
+                        }),
+                        None => pair_met[pair.0 * player_count + pair.1] = Some(round),
+                    }
+                }
+            }
+        }
+        if table_count != tables.len() {
+            violations.push(Violation::TableCount {
+                round,
+                expected: tables.len(),
+                found: table_count,
+            });
+        }
+        for (player, is_seated) in seated.iter().enumerate() {
+            if !is_seated {
+                violations.push(Violation::MissingPlayer { round, player });
+            }
+        }
+    }
+
+    for required in constraints.required_pairs.iter() {
+        let pair = (required.0.min(required.1), required.0.max(required.1));
+        let has_met = pair.1 < player_count
+            && pair.0 != pair.1
+            && pair_met[pair.0 * player_count + pair.1].is_some();
+        if !has_met {
+            violations.push(Violation::RequiredPairNeverMet { players: pair });
+        }
+    }
+    violations
+}
+
+/// Checks a schedule given as whole rounds of players in order of table, the format used by
+/// `DFScheduler` and `Solution::get_seats`
+///
+/// A final round with too few players is reported as short tables and missing players.
+pub fn verify_seats(
+    tables: &[usize],
+    seats: &[usize],
+    constraints: &Constraints,
+) -> Vec<Violation> {
+    let player_count: usize = tables.iter().sum();
+    if player_count == 0 {
+        return verify(tables, core::iter::empty::<Vec<&[usize]>>(), constraints);
+    }
+    let rounds = seats.chunks(player_count).map(|round| {
+        let mut start = 0;
+        tables
+            .iter()
+            .map(|size| {
+                let end = (start + size).min(round.len());
+                let players = &round[start..end];
+                start = end;
+                players
+            })
+            .collect::<Vec<_>>()
+    });
+    verify(tables, rounds, constraints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify, Constraints, Violation};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    const TABLES: [usize; 3] = [2, 2, 2];
+
+    fn verify_after_first_round(second_round: [&[usize]; 3]) -> Vec<Violation> {
+        let first_round: [&[usize]; 3] = [&[0, 1], &[2, 3], &[4, 5]];
+        verify(
+            &TABLES,
+            vec![first_round, second_round],
+            &Constraints::default(),
+        )
+    }
+
+    #[test]
+    fn accepts_valid_schedule() {
+        assert_eq!(
+            verify_after_first_round([&[2, 4], &[0, 5], &[1, 3]]),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn rejects_repeated_pair() {
+        let violations = verify_after_first_round([&[4, 5], &[0, 1], &[2, 3]]);
+        assert!(violations.contains(&Violation::RepeatedPair {
+            players: (4, 5),
+            first_round: 0,
+            round: 1,
+        }));
+    }
+
+    #[test]
+    fn rejects_repeated_table() {
+        let violations = verify_after_first_round([&[0, 4], &[2, 5], &[1, 3]]);
+        assert_eq!(
+            violations,
+            vec![
+                Violation::RepeatedTable {
+                    player: 0,
+                    table: 0,
+                    first_round: 0,
+                    round: 1,
+                },
+                Violation::RepeatedTable {
+                    player: 2,
+                    table: 1,
+                    first_round: 0,
+                    round: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_double_booked_player() {
+        let violations = verify_after_first_round([&[2, 4], &[0, 5], &[1, 2]]);
+        assert_eq!(
+            violations,
+            vec![
+                Violation::DuplicateSeat {
+                    round: 1,
+                    player: 2,
+                },
+                Violation::MissingPlayer {
+                    round: 1,
+                    player: 3,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::bitset::{BitSet, BitSetIter};
+use crate::schedule::Schedule;
+use crate::word::{for_each_schedule_word, Word};
+
+/// Read-only access to a block of a `Schedule`, such as the current state of a search
+#[derive(Copy, Clone, Debug)]
+pub struct ScheduleView<'v, 'a, W: Word = usize> {
+    schedule: &'v Schedule<'a, W>,
+    buffer: &'v [W],
+}
+
+impl<'a, W: Word> Schedule<'a, W> {
+    /// Returns `None` if `buffer` is shorter than a block
+    pub fn view<'v>(&'v self, buffer: &'v [W]) -> Option<ScheduleView<'v, 'a, W>> {
+        if buffer.len() < self.get_block_size() {
+            return None;
+        }
+        Some(ScheduleView {
+            schedule: self,
+            buffer: &buffer[..self.get_block_size()],
+        })
+    }
+}
+
+impl<'v, 'a, W: Word> ScheduleView<'v, 'a, W> {
+    pub fn get_scheduler(&self) -> &'v Schedule<'a, W> {
+        self.schedule
+    }
+
+    fn contains(&self, round: usize, table: usize) -> bool {
+        round < self.schedule.get_rounds() && table < self.schedule.get_tables().len()
+    }
+
+    fn player_set(&self, words: &'v [W]) -> PlayerSet<'v, W> {
+        PlayerSet {
+            set: BitSet::new(words, self.schedule.get_player_count()),
+        }
+    }
+
+    /// Players fixed at a table
+    pub fn fixed(&self, round: usize, table: usize) -> Option<PlayerSet<'v, W>> {
+        if !self.contains(round, table) {
+            return None;
+        }
+        Some(self.player_set(self.schedule.get_fixed_words(self.buffer, round, table)))
+    }
+
+    /// Players which may still sit at a table, including those fixed there
+    pub fn candidates(&self, round: usize, table: usize) -> Option<PlayerSet<'v, W>> {
+        if !self.contains(round, table) {
+            return None;
+        }
+        Some(self.player_set(self.schedule.get_potential_words(self.buffer, round, table)))
+    }
+
+    /// Players that `player` has sat with
+    pub fn met(&self, player: usize) -> Option<PlayerSet<'v, W>> {
+        if player >= self.schedule.get_player_count() {
+            return None;
+        }
+        Some(self.player_set(self.schedule.get_played_with_words(self.buffer, player)))
+    }
+
+    /// Players that have sat at a table in any round
+    pub fn visited(&self, table: usize) -> Option<PlayerSet<'v, W>> {
+        if table >= self.schedule.get_tables().len() {
+            return None;
+        }
+        Some(
+            self.player_set(
+                self.schedule
+                    .get_played_on_table_total_words(self.buffer, table),
+            ),
+        )
+    }
+
+    /// Players that have a table in a round
+    pub fn placed_in_round(&self, round: usize) -> Option<PlayerSet<'v, W>> {
+        if round >= self.schedule.get_rounds() {
+            return None;
+        }
+        Some(self.player_set(self.schedule.get_played_in_round_words(self.buffer, round)))
+    }
+}
+
+macro_rules! impl_view {
+    ($w: ty) => {
+        impl<'v, 'a> ScheduleView<'v, 'a, $w> {
+            pub fn get_players_placed(&self) -> usize {
+                self.schedule.get_players_placed(self.buffer)
+            }
+
+            pub fn get_empty_table_count(&self) -> usize {
+                self.schedule.get_empty_table_count(self.buffer)
+            }
+
+            /// Whether a table is still waiting to be filled
+            ///
+            /// Tables filled by `fill_forced_table` stay marked to explore until the next step,
+            /// so a solved block can still have them marked.
+            pub fn is_open(&self, round: usize, table: usize) -> bool {
+                self.contains(round, table)
+                    && self.schedule.is_to_explore(self.buffer, round, table)
+                    && self.fixed(round, table).unwrap().len() < self.schedule.get_tables()[table]
+            }
+
+            /// (round, table) of each table still waiting to be filled
+            pub fn open_tables(&self) -> impl Iterator<Item = (usize, usize)> + 'v {
+                let view = *self;
+                let table_count = self.schedule.get_tables().len();
+                (0..self.schedule.get_rounds() * table_count)
+                    .map(move |number| (number / table_count, number % table_count))
+                    .filter(move |(round, table)| view.is_open(*round, *table))
+            }
+        }
+    };
+}
+
+for_each_schedule_word!(impl_view);
+
+/// A set of players, stored as one bit per player
+#[derive(Copy, Clone, Debug)]
+pub struct PlayerSet<'a, W: Word = usize> {
+    set: BitSet<'a, W>,
+}
+
+impl<'a, W: Word> PlayerSet<'a, W> {
+    pub fn contains(&self, player: usize) -> bool {
+        W::contains_in(self.set, player)
+    }
+
+    pub fn len(&self) -> usize {
+        W::count_ones_in(self.set)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        W::first_set_in(self.set, 0).is_none()
+    }
+
+    pub fn iter(&self) -> BitSetIter<'a, W> {
+        self.set.iter()
+    }
+
+    pub fn as_bitset(&self) -> BitSet<'a, W> {
+        self.set
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::builder::ScheduleBuilder;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn view_of_a_solution_matches_it() {
+        let tables = [3, 3, 3, 3];
+        let schedule = ScheduleBuilder::new(&tables).rounds(3).build().unwrap();
+        let mut buffer = vec![0; schedule.get_search_buffer_size()];
+        let mut search = schedule.fixed_search(&mut buffer).unwrap();
+        let block = search.solve().unwrap();
+        let solution = schedule.to_solution(block).unwrap();
+        let view = schedule.view(block).unwrap();
+        let players = solution.get_player_count();
+
+        assert!(view.fixed(solution.get_round_count(), 0).is_none());
+        assert!(view.fixed(0, tables.len()).is_none());
+        assert!(view.met(players).is_none());
+        assert_eq!(view.open_tables().count(), 0);
+        for round in 0..solution.get_round_count() {
+            assert_eq!(view.placed_in_round(round).unwrap().len(), players);
+            for table in 0..tables.len() {
+                let fixed: Vec<usize> = view.fixed(round, table).unwrap().iter().collect();
+                assert_eq!(fixed, solution.players_at(round, table).unwrap());
+                for player in 0..players {
+                    assert_eq!(
+                        view.fixed(round, table).unwrap().contains(player),
+                        solution.table_of(player, round) == Some(table)
+                    );
+                }
+            }
+        }
+        for player in 0..players {
+            let met = view.met(player).unwrap();
+            assert!(!met.contains(player));
+            for other in (0..players).filter(|other| *other != player) {
+                let has_met = solution.rounds_met(player, other).next().is_some();
+                assert_eq!(met.contains(other), has_met);
+            }
+        }
+        for table in 0..tables.len() {
+            let visited = view.visited(table).unwrap();
+            for player in 0..players {
+                let has_visited = (0..solution.get_round_count())
+                    .any(|round| solution.table_of(player, round) == Some(table));
+                assert_eq!(visited.contains(player), has_visited);
+            }
+        }
+    }
+}
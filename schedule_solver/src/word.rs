@@ -0,0 +1,154 @@
+use crate::bitset::{BitSet, BitSetMut};
+use core::ops::*;
+
+/// An unsigned or signed integer used as a word of player bits
+pub trait Word:
+    Sized
+    + BitAnd<Self, Output = Self>
+    + BitAndAssign<Self>
+    + BitOr<Self, Output = Self>
+    + BitOrAssign<Self>
+    + BitXor<Self, Output = Self>
+    + BitXorAssign<Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+    + Sub<Self, Output = Self>
+    + SubAssign<Self>
+    + Not<Output = Self>
+    + Copy
+    + Clone
+    + core::fmt::Debug
+    + core::fmt::Binary
+    + Eq
+{
+    const SIZE: usize = 8 * core::mem::size_of::<Self>();
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+    fn count_ones(self) -> u32;
+    fn count_zeros(self) -> u32;
+    fn leading_ones(self) -> u32;
+    fn leading_zeros(self) -> u32;
+    fn trailing_ones(self) -> u32;
+    fn trailing_zeros(self) -> u32;
+    /// Writes `self` to the first `SIZE / 8` bytes of `bytes`, least significant first
+    fn write_le_bytes(self, bytes: &mut [u8]);
+    /// Reads a word written by `write_le_bytes`
+    fn read_le_bytes(bytes: &[u8]) -> Self;
+
+    // The const `BitSet` operations, for code which is generic over the word type
+    fn count_ones_in(set: BitSet<'_, Self>) -> usize;
+    fn contains_in(set: BitSet<'_, Self>, bit: usize) -> bool;
+    fn first_set_in(set: BitSet<'_, Self>, start: usize) -> Option<usize>;
+    fn insert_in(set: &mut BitSetMut<'_, Self>, bit: usize);
+    fn remove_in(set: &mut BitSetMut<'_, Self>, bit: usize);
+}
+
+macro_rules! derive_word {
+    ($x: ty) => {
+        impl Word for $x {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MAX: Self = Self::MAX;
+            #[inline(always)]
+            fn count_ones(self) -> u32 {
+                self.count_ones()
+            }
+            #[inline(always)]
+            fn count_zeros(self) -> u32 {
+                self.count_zeros()
+            }
+            #[inline(always)]
+            fn leading_ones(self) -> u32 {
+                self.leading_ones()
+            }
+            #[inline(always)]
+            fn leading_zeros(self) -> u32 {
+                self.leading_zeros()
+            }
+            #[inline(always)]
+            fn trailing_ones(self) -> u32 {
+                self.trailing_ones()
+            }
+            #[inline(always)]
+            fn trailing_zeros(self) -> u32 {
+                self.trailing_zeros()
+            }
+            #[inline(always)]
+            fn write_le_bytes(self, bytes: &mut [u8]) {
+                bytes[..Self::SIZE / 8].copy_from_slice(&self.to_le_bytes());
+            }
+            #[inline(always)]
+            fn read_le_bytes(bytes: &[u8]) -> Self {
+                let mut word = [0; Self::SIZE / 8];
+                word.copy_from_slice(&bytes[..Self::SIZE / 8]);
+                Self::from_le_bytes(word)
+            }
+            #[inline(always)]
+            fn count_ones_in(set: BitSet<'_, Self>) -> usize {
+                set.count_ones()
+            }
+            #[inline(always)]
+            fn contains_in(set: BitSet<'_, Self>, bit: usize) -> bool {
+                set.contains(bit)
+            }
+            #[inline(always)]
+            fn first_set_in(set: BitSet<'_, Self>, start: usize) -> Option<usize> {
+                set.first_set_from(start)
+            }
+            #[inline(always)]
+            fn insert_in(set: &mut BitSetMut<'_, Self>, bit: usize) {
+                set.insert(bit)
+            }
+            #[inline(always)]
+            fn remove_in(set: &mut BitSetMut<'_, Self>, bit: usize) {
+                set.remove(bit)
+            }
+        }
+    };
+}
+
+derive_word!(u8);
+derive_word!(u16);
+derive_word!(u32);
+derive_word!(u64);
+derive_word!(u128);
+derive_word!(usize);
+derive_word!(i8);
+derive_word!(i16);
+derive_word!(i32);
+derive_word!(i64);
+derive_word!(i128);
+derive_word!(isize);
+
+/// Word types with a concrete, const `Schedule` implementation
+///
+/// A block also stores its counters in words, so words narrower than 32 bits are left out, and
+/// `SimdWord` is left out for the reasons in its module.
+macro_rules! for_each_schedule_word {
+    ($impl_macro: ident) => {
+        $impl_macro!(u32);
+        $impl_macro!(u64);
+        $impl_macro!(u128);
+        $impl_macro!(usize);
+    };
+}
+pub(crate) use for_each_schedule_word;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Word types which `Schedule` is implemented for, as listed in `for_each_schedule_word!`
+///
+/// This is sealed, as a `Schedule` of any other word would have none of its methods.
+pub trait ScheduleWord: Word + sealed::Sealed {}
+
+macro_rules! impl_schedule_word {
+    ($w: ty) => {
+        impl sealed::Sealed for $w {}
+        impl ScheduleWord for $w {}
+    };
+}
+
+for_each_schedule_word!(impl_schedule_word);
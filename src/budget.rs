@@ -0,0 +1,74 @@
+use std::time::{Duration, Instant};
+
+/// How often the clock is read, in steps
+pub(crate) const TIME_CHECK_INTERVAL: usize = 256;
+
+/// Limits for a budgeted solve, where `None` means unlimited
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Budget {
+    pub time: Option<Duration>,
+    pub steps: Option<usize>,
+}
+
+impl Budget {
+    pub const UNLIMITED: Self = Self {
+        time: None,
+        steps: None,
+    };
+
+    pub fn time(limit: Duration) -> Self {
+        Self::UNLIMITED.with_time(limit)
+    }
+
+    pub fn steps(limit: usize) -> Self {
+        Self::UNLIMITED.with_steps(limit)
+    }
+
+    pub fn with_time(mut self, limit: Duration) -> Self {
+        self.time = Some(limit);
+        self
+    }
+
+    pub fn with_steps(mut self, limit: usize) -> Self {
+        self.steps = Some(limit);
+        self
+    }
+
+    pub fn is_spent(&self, started: Instant, steps: usize) -> bool {
+        self.steps.map(|limit| steps >= limit).unwrap_or(false)
+            || (steps.is_multiple_of(TIME_CHECK_INTERVAL)
+                && self
+                    .time
+                    .map(|limit| started.elapsed() >= limit)
+                    .unwrap_or(false))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BudgetStatus {
+    /// `best` is a complete schedule
+    Complete,
+    /// The search ran out of options without a complete schedule, and `best` is the deepest
+    /// partial schedule it reached
+    ///
+    /// Pruning, hidden singles and forced tables discard partial schedules which cannot be
+    /// completed, so a deeper partial schedule may exist.
+    Exhausted,
+    /// The budget ran out first
+    OutOfBudget,
+    /// The solve's `CancellationToken` was cancelled first
+    Cancelled,
+}
+
+#[derive(Clone, Debug)]
+pub struct BudgetedResult {
+    /// The best schedule found, in the solver's own format
+    pub best: Vec<usize>,
+    /// The solver's measure of how good `best` is, where higher is better
+    pub score: usize,
+    pub status: BudgetStatus,
+    pub steps: usize,
+    pub elapsed: Duration,
+    /// The solver's statistics when it stopped
+    pub stats: schedule_solver::SearchStats,
+}
@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A flag shared between threads, which solvers check between steps
+///
+/// Clones share the same flag, so one clone can be handed to another thread to cancel a solve.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CancellationToken;
+    use crate::budget::{Budget, BudgetStatus};
+    use crate::df_schedule::DFScheduler;
+    use crate::search::Search;
+    use schedule_solver::ScheduleBuilder;
+    use std::num::NonZeroUsize;
+    use std::thread;
+    use std::time::Duration;
+
+    /// Neither solver finishes this within a few seconds
+    const TABLES: [usize; 6] = [3; 6];
+    const ROUNDS: usize = 6;
+    const SEATS: usize = 3 * 6 * ROUNDS;
+    /// Stops the test if the cancellation is missed
+    const LIMIT: Duration = Duration::from_secs(60);
+
+    fn cancel_later(cancel: &CancellationToken) -> thread::JoinHandle<()> {
+        let cancel = cancel.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            cancel.cancel();
+        })
+    }
+
+    fn groups() -> Vec<NonZeroUsize> {
+        TABLES
+            .iter()
+            .map(|size| NonZeroUsize::new(*size).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn search_cancelled_before_solving() {
+        let schedule = ScheduleBuilder::new(&TABLES)
+            .rounds(ROUNDS)
+            .build()
+            .unwrap();
+        let mut search = Search::new(schedule).unwrap();
+        let start = search.get_current().to_vec();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = search.solve_cancellable(Budget::UNLIMITED, &cancel);
+        assert_eq!(result.status, BudgetStatus::Cancelled);
+        assert_eq!(result.steps, 0);
+        assert_eq!(result.best, start);
+    }
+
+    #[test]
+    fn search_cancelled_from_another_thread() {
+        let schedule = ScheduleBuilder::new(&TABLES)
+            .rounds(ROUNDS)
+            .build()
+            .unwrap();
+        let mut search = Search::new(schedule).unwrap();
+        let cancel = CancellationToken::new();
+        let canceller = cancel_later(&cancel);
+        let result = search.solve_cancellable(Budget::time(LIMIT), &cancel);
+        canceller.join().unwrap();
+        assert_eq!(result.status, BudgetStatus::Cancelled);
+        assert!(result.steps > 0);
+        assert!(result.score > 0 && result.score < SEATS, "{}", result.score);
+        assert_eq!(
+            search.get_scheduler().get_players_placed(&result.best),
+            result.score
+        );
+    }
+
+    #[test]
+    fn df_scheduler_cancelled_before_solving() {
+        let mut scheduler = DFScheduler::<u64>::new(&groups()).with_rounds(ROUNDS);
+        let start = scheduler.get_schedule().clone();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = scheduler.solve_cancellable(Budget::UNLIMITED, &cancel);
+        assert_eq!(result.status, BudgetStatus::Cancelled);
+        assert_eq!(result.steps, 0);
+        assert_eq!(result.best, start);
+    }
+
+    #[test]
+    fn df_scheduler_cancelled_from_another_thread() {
+        let mut scheduler = DFScheduler::<u64>::new(&groups()).with_rounds(ROUNDS);
+        let cancel = CancellationToken::new();
+        let canceller = cancel_later(&cancel);
+        let result = scheduler.solve_cancellable(Budget::time(LIMIT), &cancel);
+        canceller.join().unwrap();
+        assert_eq!(result.status, BudgetStatus::Cancelled);
+        assert!(result.steps > 0);
+        assert!(result.score > 0 && result.score < SEATS, "{}", result.score);
+        assert_eq!(result.best.len(), result.score);
+    }
+}
@@ -0,0 +1,376 @@
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const MAGIC: &[u8; 8] = b"DFSCHED\0";
+/// Bumped whenever the layout of a checkpoint changes
+pub const CHECKPOINT_VERSION: u32 = 4;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CheckpointKind {
+    Search,
+    DFScheduler,
+}
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(std::io::Error),
+    /// The file is not a checkpoint
+    BadMagic,
+    UnsupportedVersion(u32),
+    WrongKind {
+        expected: CheckpointKind,
+        found: CheckpointKind,
+    },
+    /// The checkpoint was written with different tables
+    TablesMismatch {
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+    RoundsMismatch {
+        expected: usize,
+        found: usize,
+    },
+    /// The checkpoint was written by a `DFScheduler` with a different word type
+    WordSizeMismatch {
+        expected: usize,
+        found: usize,
+    },
+    /// The checkpoint's contents are inconsistent with its tables
+    Corrupt,
+}
+
+impl std::fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::BadMagic => write!(f, "not a checkpoint file"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "checkpoint version {} is not supported, expected {}",
+                version, CHECKPOINT_VERSION
+            ),
+            Self::WrongKind { expected, found } => {
+                write!(f, "checkpoint is for {:?}, expected {:?}", found, expected)
+            }
+            Self::TablesMismatch { expected, found } => write!(
+                f,
+                "checkpoint has tables {:?}, expected {:?}",
+                found, expected
+            ),
+            Self::RoundsMismatch { expected, found } => {
+                write!(f, "checkpoint has {} rounds, expected {}", found, expected)
+            }
+            Self::WordSizeMismatch { expected, found } => write!(
+                f,
+                "checkpoint has {} bit words, expected {}",
+                found, expected
+            ),
+            Self::Corrupt => write!(f, "checkpoint is corrupt"),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CheckpointError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// State which can be written to a checkpoint and resumed from later
+pub trait Checkpoint {
+    fn save_checkpoint(&self, writer: &mut dyn Write) -> std::io::Result<()>;
+}
+
+/// Writes checkpoints to a file, at most once per `interval`
+///
+/// Each checkpoint is written to a temporary file and renamed over `path`, so a crash while
+/// saving leaves the previous checkpoint intact.
+#[derive(Debug)]
+pub struct Checkpointer {
+    path: PathBuf,
+    interval: Duration,
+    last_saved: Instant,
+}
+
+impl Checkpointer {
+    pub fn new(path: impl Into<PathBuf>, interval: Duration) -> Self {
+        Self {
+            path: path.into(),
+            interval,
+            last_saved: Instant::now(),
+        }
+    }
+
+    pub fn get_path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.last_saved.elapsed() >= self.interval
+    }
+
+    pub fn save(&mut self, state: &dyn Checkpoint) -> std::io::Result<()> {
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        {
+            let mut writer = std::io::BufWriter::new(std::fs::File::create(&temp_path)?);
+            state.save_checkpoint(&mut writer)?;
+            writer.into_inner()?.sync_all()?;
+        }
+        std::fs::rename(&temp_path, &self.path)?;
+        self.last_saved = Instant::now();
+        Ok(())
+    }
+
+    /// Saves if `interval` has passed since the last save, returning whether it did
+    pub fn save_if_due(&mut self, state: &dyn Checkpoint) -> std::io::Result<bool> {
+        if !self.is_due() {
+            return Ok(false);
+        }
+        self.save(state)?;
+        Ok(true)
+    }
+
+    /// Opens the checkpoint at `path`, or returns `None` if there is none yet
+    pub fn open(&self) -> std::io::Result<Option<std::io::BufReader<std::fs::File>>> {
+        match std::fs::File::open(&self.path) {
+            Ok(file) => Ok(Some(std::io::BufReader::new(file))),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+pub(crate) fn write_header(
+    writer: &mut dyn Write,
+    kind: CheckpointKind,
+    word_size: usize,
+    tables: &[usize],
+) -> std::io::Result<()> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&CHECKPOINT_VERSION.to_le_bytes())?;
+    writer.write_all(&[kind as u8])?;
+    write_usize(writer, word_size)?;
+    write_usizes(writer, tables)
+}
+
+/// Checks the header matches, leaving `reader` at the start of the state
+pub(crate) fn read_header(
+    reader: &mut dyn Read,
+    kind: CheckpointKind,
+    word_size: usize,
+    tables: &[usize],
+) -> Result<(), CheckpointError> {
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(CheckpointError::BadMagic);
+    }
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version != CHECKPOINT_VERSION {
+        return Err(CheckpointError::UnsupportedVersion(version));
+    }
+    let mut found_kind = [0];
+    reader.read_exact(&mut found_kind)?;
+    let found_kind = match found_kind[0] {
+        0 => CheckpointKind::Search,
+        1 => CheckpointKind::DFScheduler,
+        _ => return Err(CheckpointError::Corrupt),
+    };
+    if found_kind != kind {
+        return Err(CheckpointError::WrongKind {
+            expected: kind,
+            found: found_kind,
+        });
+    }
+    let found_word_size = read_usize(reader)?;
+    if found_word_size != word_size {
+        return Err(CheckpointError::WordSizeMismatch {
+            expected: word_size,
+            found: found_word_size,
+        });
+    }
+    let found_tables = read_usizes(reader)?;
+    if found_tables != tables {
+        return Err(CheckpointError::TablesMismatch {
+            expected: tables.to_vec(),
+            found: found_tables,
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn write_usize(writer: &mut dyn Write, value: usize) -> std::io::Result<()> {
+    writer.write_all(&(value as u64).to_le_bytes())
+}
+
+pub(crate) fn read_usize(reader: &mut dyn Read) -> Result<usize, CheckpointError> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    usize::try_from(u64::from_le_bytes(bytes)).map_err(|_| CheckpointError::Corrupt)
+}
+
+/// Writes the length, then each value
+pub(crate) fn write_usizes(writer: &mut dyn Write, values: &[usize]) -> std::io::Result<()> {
+    write_usize(writer, values.len())?;
+    for value in values.iter() {
+        write_usize(writer, *value)?;
+    }
+    Ok(())
+}
+
+/// Reads values written by `write_usizes`
+pub(crate) fn read_usizes(reader: &mut dyn Read) -> Result<Vec<usize>, CheckpointError> {
+    let len = read_usize(reader)?;
+    (0..len).map(|_| read_usize(reader)).collect()
+}
+
+pub(crate) fn write_stats(
+    writer: &mut dyn Write,
+    stats: &schedule_solver::SearchStats,
+) -> std::io::Result<()> {
+    write_usizes(
+        writer,
+        &[
+            stats.nodes,
+            stats.backtracks,
+            stats.hidden_singles,
+            stats.forced_tables,
+            stats.probe_eliminations,
+            stats.contradictions.overfull_table,
+            stats.contradictions.no_candidates,
+            stats.contradictions.opponent_shortfall,
+            stats.max_depth,
+        ],
+    )?;
+    write_usize(writer, stats.elapsed.as_secs() as usize)?;
+    write_usize(writer, stats.elapsed.subsec_nanos() as usize)
+}
+
+pub(crate) fn read_stats(
+    reader: &mut dyn Read,
+) -> Result<schedule_solver::SearchStats, CheckpointError> {
+    let counters = read_usizes(reader)?;
+    if counters.len() != 9 {
+        return Err(CheckpointError::Corrupt);
+    }
+    let secs = read_usize(reader)? as u64;
+    let nanos = read_usize(reader)?;
+    if nanos >= 1_000_000_000 {
+        return Err(CheckpointError::Corrupt);
+    }
+    Ok(schedule_solver::SearchStats {
+        nodes: counters[0],
+        backtracks: counters[1],
+        hidden_singles: counters[2],
+        forced_tables: counters[3],
+        probe_eliminations: counters[4],
+        contradictions: schedule_solver::Contradictions {
+            overfull_table: counters[5],
+            no_candidates: counters[6],
+            opponent_shortfall: counters[7],
+        },
+        max_depth: counters[8],
+        elapsed: Duration::new(secs, nanos as u32),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Checkpoint, CheckpointError, CheckpointKind};
+    use crate::df_schedule::DFScheduler;
+    use crate::search::Search;
+    use schedule_solver::ScheduleBuilder;
+    use std::num::NonZeroUsize;
+
+    fn save(state: &dyn Checkpoint) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        state.save_checkpoint(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn search_round_trip() {
+        let tables = [3; 5];
+        let schedule = || ScheduleBuilder::new(&tables).rounds(3).build().unwrap();
+        let mut search = Search::new(schedule()).unwrap();
+        for _ in 0..50 {
+            search.step().unwrap();
+        }
+        let saved = save(&search);
+        let mut resumed = Search::resume(schedule(), &mut saved.as_slice()).unwrap();
+        assert_eq!(save(&resumed), saved);
+        assert_eq!(resumed.get_depth(), search.get_depth());
+        assert_eq!(resumed.get_steps(), search.get_steps());
+        assert_eq!(resumed.solve().unwrap(), search.solve().unwrap());
+    }
+
+    #[test]
+    fn df_scheduler_round_trip() {
+        let groups: Vec<_> = [3, 3, 2, 3]
+            .iter()
+            .map(|size| NonZeroUsize::new(*size).unwrap())
+            .collect();
+        let mut scheduler = DFScheduler::<u16>::new(&groups).with_rounds(3);
+        for _ in 0..200 {
+            scheduler.step().unwrap();
+        }
+        let saved = save(&scheduler);
+        let mut resumed = DFScheduler::<u16>::resume(&groups, &mut saved.as_slice()).unwrap();
+        assert_eq!(save(&resumed), saved);
+        assert_eq!(resumed.get_rounds(), 3);
+        loop {
+            let step = scheduler.step();
+            assert_eq!(resumed.step(), step);
+            assert_eq!(resumed.get_schedule(), scheduler.get_schedule());
+            if step.is_none() {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn resume_rejects_other_layouts() {
+        let tables = [3; 5];
+        let schedule = ScheduleBuilder::new(&tables).rounds(3).build().unwrap();
+        let saved = save(&Search::new(schedule).unwrap());
+
+        let other_tables = [3; 4];
+        let other = ScheduleBuilder::new(&other_tables)
+            .rounds(3)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            Search::resume(other, &mut saved.as_slice()),
+            Err(CheckpointError::TablesMismatch { .. })
+        ));
+        let groups = vec![NonZeroUsize::new(3).unwrap(); 5];
+        assert!(matches!(
+            DFScheduler::<usize>::resume(&groups, &mut saved.as_slice()),
+            Err(CheckpointError::WrongKind {
+                expected: CheckpointKind::DFScheduler,
+                found: CheckpointKind::Search,
+            })
+        ));
+        assert!(matches!(
+            Search::resume(
+                ScheduleBuilder::new(&tables).rounds(3).build().unwrap(),
+                &mut &saved[..saved.len() - 1]
+            ),
+            Err(CheckpointError::Io(_)) | Err(CheckpointError::Corrupt)
+        ));
+    }
+}
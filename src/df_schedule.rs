@@ -1,82 +1,64 @@
-use core::ops::*;
-
-pub trait Word:
-    Sized
-    + BitAnd<Self, Output = Self>
-    + BitAndAssign<Self>
-    + BitOr<Self, Output = Self>
-    + BitOrAssign<Self>
-    + BitXor<Self, Output = Self>
-    + BitXorAssign<Self>
-    + Shl<usize, Output = Self>
-    + Shr<usize, Output = Self>
-    + Sub<Self, Output = Self>
-    + SubAssign<Self>
-    + Not<Output = Self>
-    + Copy
-    + Clone
-    + std::fmt::Debug
-    + std::fmt::Binary
-    + Eq
-{
-    const SIZE: usize = 8 * std::mem::size_of::<Self>();
-    const ZERO: Self;
-    const ONE: Self;
-    const MAX: Self;
-    fn count_ones(self) -> u32;
-    fn count_zeros(self) -> u32;
-    fn leading_ones(self) -> u32;
-    fn leading_zeros(self) -> u32;
-    fn trailing_ones(self) -> u32;
-    fn trailing_zeros(self) -> u32;
+use crate::checkpoint::{self, Checkpoint, CheckpointError, CheckpointKind};
+use schedule_solver::{BitSet, BitSetMut};
+
+pub use schedule_solver::Word;
+
+/// What a call to `DFScheduler::step` did
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DFStep {
+    /// A player was placed, making the schedule this long
+    Advanced(usize),
+    Backtracked,
+    /// A player was placed and the schedule has reached the round target
+    Complete,
 }
 
-macro_rules! derive_word {
-    ($x: ty) => {
-        impl Word for $x {
-            const ZERO: Self = 0;
-            const ONE: Self = 1;
-            const MAX: Self = Self::MAX;
-            #[inline(always)]
-            fn count_ones(self) -> u32 {
-                self.count_ones()
-            }
-            #[inline(always)]
-            fn count_zeros(self) -> u32 {
-                self.count_zeros()
-            }
-            #[inline(always)]
-            fn leading_ones(self) -> u32 {
-                self.leading_ones()
-            }
-            #[inline(always)]
-            fn leading_zeros(self) -> u32 {
-                self.leading_zeros()
-            }
-            #[inline(always)]
-            fn trailing_ones(self) -> u32 {
-                self.trailing_ones()
-            }
-            #[inline(always)]
-            fn trailing_zeros(self) -> u32 {
-                self.trailing_zeros()
+/// Why `DFScheduler::replay` rejected a prefix
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The prefix is longer than a schedule reaching the round target
+    TooLong {
+        length: usize,
+        complete_length: usize,
+    },
+    /// The seat at `position` is not one the search could place there
+    InvalidSeat { position: usize, player: usize },
+    /// The seat at `position` is valid, but breaks the order in which the search seats players,
+    /// which is increasing at each table and by the first player of each round
+    OutOfOrder { position: usize, player: usize },
+    /// Closing the table at `position` leaves a player unable to meet enough new opponents
+    OpponentShortfall { position: usize },
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooLong {
+                length,
+                complete_length,
+            } => write!(
+                f,
+                "the prefix has {} seats, but a complete schedule has {}",
+                length, complete_length
+            ),
+            Self::InvalidSeat { position, player } => {
+                write!(f, "player {} cannot sit at position {}", player, position)
             }
+            Self::OutOfOrder { position, player } => write!(
+                f,
+                "player {} at position {} is out of order for the search",
+                player, position
+            ),
+            Self::OpponentShortfall { position } => write!(
+                f,
+                "the table closed at position {} leaves a player short of opponents",
+                position
+            ),
         }
-    };
+    }
 }
 
-derive_word!(u8);
-derive_word!(u16);
-derive_word!(u32);
-derive_word!(u64);
-derive_word!(u128);
-derive_word!(usize);
-derive_word!(i8);
-derive_word!(i16);
-derive_word!(i32);
-derive_word!(i64);
-derive_word!(i128);
-derive_word!(isize);
+impl std::error::Error for ReplayError {}
 
 #[derive(Debug)]
 pub struct DFScheduler<T>
@@ -84,6 +66,8 @@ where
     T: Word,
 {
     groups: Box<[std::num::NonZeroUsize]>,
+    /// The round target, after which no further round is opened
+    rounds: usize,
     player_count: usize,
     player_bit_word_count: usize,
     players_played_with: Box<[T]>,
@@ -98,12 +82,20 @@ where
     min_player: Option<usize>,
     temp_buffer: Box<[T]>,
     best_length: usize,
+    /// Length which backtracking never goes below, keeping the first round or the prefix given
+    /// to `replay`
+    floor: usize,
+    stats: schedule_solver::SearchStats,
+    /// Whether the last placement was cut off by `has_opponent_shortfall`, so that the backtrack
+    /// that follows is not counted as running out of candidates
+    pruned: bool,
 }
 
 impl<T: Word> Clone for DFScheduler<T> {
     fn clone(&self) -> Self {
         Self {
             groups: self.groups.clone(),
+            rounds: self.rounds,
             player_count: self.player_count,
             player_bit_word_count: self.player_bit_word_count,
             players_played_with: self.players_played_with.clone(),
@@ -118,10 +110,14 @@ impl<T: Word> Clone for DFScheduler<T> {
             min_player: self.min_player,
             temp_buffer: self.temp_buffer.clone(),
             best_length: self.best_length,
+            floor: self.floor,
+            stats: self.stats,
+            pruned: self.pruned,
         }
     }
     fn clone_from(&mut self, other: &Self) {
         self.groups.clone_from(&other.groups);
+        self.rounds = other.rounds;
         self.player_count = other.player_count;
         self.player_bit_word_count = other.player_bit_word_count;
         self.players_played_with
@@ -139,10 +135,14 @@ impl<T: Word> Clone for DFScheduler<T> {
         self.min_player.clone_from(&other.min_player);
         self.temp_buffer.clone_from(&other.temp_buffer);
         self.best_length.clone_from(&other.best_length);
+        self.floor = other.floor;
+        self.stats.clone_from(&other.stats);
+        self.pruned = other.pruned;
     }
 }
 
 impl<T: Word> DFScheduler<T> {
+    /// Starts with one round per table, as players never return to a table
     pub fn new(groups: &[std::num::NonZeroUsize]) -> Self {
         let player_count = groups.iter().map(|x| x.get()).sum();
         let player_bit_word_count = player_count / T::SIZE + (player_count % T::SIZE != 0) as usize;
@@ -156,6 +156,7 @@ impl<T: Word> DFScheduler<T> {
 
         Self {
             groups: groups.to_vec().into_boxed_slice(),
+            rounds: groups.len(),
             player_count,
             player_bit_word_count,
             players_played_with,
@@ -170,7 +171,99 @@ impl<T: Word> DFScheduler<T> {
             min_player: None,
             temp_buffer,
             best_length: 0,
+            floor: player_count,
+            stats: schedule_solver::SearchStats::default(),
+            pruned: false,
+        }
+    }
+
+    /// Searches for `rounds` rounds instead, which must be at least one and at most the number
+    /// of tables
+    pub fn with_rounds(mut self, rounds: usize) -> Self {
+        assert!(
+            rounds > 0 && rounds <= self.groups.len(),
+            "{} rounds is not between 1 and the {} tables",
+            rounds,
+            self.groups.len()
+        );
+        self.rounds = rounds;
+        self
+    }
+
+    pub fn get_rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// Length which backtracking never goes below, which is the first round unless `replay` set
+    /// it
+    pub fn get_floor(&self) -> usize {
+        self.floor
+    }
+
+    /// Continues a search saved with `save_checkpoint`, which must have used the same groups and
+    /// word type, taking its round target from the checkpoint
+    pub fn resume(
+        groups: &[std::num::NonZeroUsize],
+        reader: &mut dyn std::io::Read,
+    ) -> Result<Self, CheckpointError> {
+        let mut scheduler = Self::new(groups);
+        let tables: Vec<usize> = groups.iter().map(|size| size.get()).collect();
+        checkpoint::read_header(reader, CheckpointKind::DFScheduler, T::SIZE, &tables)?;
+        scheduler.rounds = checkpoint::read_usize(reader)?;
+        scheduler.schedule = checkpoint::read_usizes(reader)?;
+        scheduler.best_length = checkpoint::read_usize(reader)?;
+        scheduler.floor = checkpoint::read_usize(reader)?;
+        scheduler.on_current_table_offset = checkpoint::read_usize(reader)?;
+        scheduler.current_table = checkpoint::read_usize(reader)?;
+        scheduler.current_position_in_table = checkpoint::read_usize(reader)?;
+        scheduler.current_round = checkpoint::read_usize(reader)?;
+        scheduler.min_player = match checkpoint::read_usize(reader)? {
+            0 => None,
+            _ => Some(checkpoint::read_usize(reader)?),
+        };
+        scheduler.stats = checkpoint::read_stats(reader)?;
+        scheduler.pruned = checkpoint::read_usize(reader)? != 0;
+        scheduler.players_played_with = read_words(reader)?.into_boxed_slice();
+        scheduler.played_on_table_total = read_words(reader)?.into_boxed_slice();
+        scheduler.played_in_round = read_words(reader)?;
+        scheduler.on_current_table = read_words(reader)?;
+        scheduler.temp_buffer = read_words(reader)?.into_boxed_slice();
+
+        let word_count = scheduler.player_bit_word_count;
+        let table_count = scheduler.groups.len();
+        let seats_in_round = scheduler.current_position_in_table
+            + scheduler.groups[..scheduler.current_table.min(table_count)]
+                .iter()
+                .map(|size| size.get())
+                .sum::<usize>();
+        let is_consistent = scheduler.rounds > 0
+            && scheduler.rounds <= table_count
+            && scheduler.current_table < table_count
+            && scheduler.current_position_in_table < groups[scheduler.current_table].get()
+            && scheduler.schedule.len()
+                == scheduler.current_round * scheduler.player_count + seats_in_round
+            && scheduler.best_length >= scheduler.schedule.len()
+            && scheduler.best_length <= scheduler.get_complete_length()
+            && scheduler.floor <= scheduler.schedule.len().max(scheduler.player_count)
+            && scheduler
+                .schedule
+                .iter()
+                .all(|player| *player < scheduler.player_count)
+            && scheduler
+                .min_player
+                .map(|player| player < scheduler.player_count)
+                .unwrap_or(true)
+            && scheduler.players_played_with.len() == word_count * scheduler.player_count
+            && scheduler.played_on_table_total.len() == word_count * table_count
+            && scheduler.played_in_round.len() == (scheduler.current_round + 1) * word_count
+            && scheduler.on_current_table_offset
+                == (scheduler.current_round * table_count + scheduler.current_table) * word_count
+            && scheduler.on_current_table.len() == scheduler.on_current_table_offset + word_count
+            && scheduler.temp_buffer.len() == word_count;
+        if !is_consistent {
+            return Err(CheckpointError::Corrupt);
         }
+        Ok(scheduler)
     }
 
     pub fn count_ones(&self) -> Vec<u32> {
@@ -199,17 +292,122 @@ impl<T: Word> DFScheduler<T> {
         &self.schedule
     }
 
+    /// The complete rounds of the current schedule
+    pub fn get_solution(&self) -> schedule_solver::Solution {
+        let tables: Vec<usize> = self.groups.iter().map(|size| size.get()).collect();
+        let complete_rounds = self.schedule.len() / self.player_count;
+        schedule_solver::Solution::from_seats(
+            &tables,
+            &self.schedule[..complete_rounds * self.player_count],
+        )
+        .expect("DFScheduler seats each player once per round")
+    }
+
+    /// Length of the schedule once it has reached the round target
+    pub fn get_complete_length(&self) -> usize {
+        self.player_count * self.rounds
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.schedule.len() == self.get_complete_length()
+    }
+
+    /// `elapsed` covers time spent in the `solve` methods, not in calls to `step`
+    pub fn get_stats(&self) -> &schedule_solver::SearchStats {
+        &self.stats
+    }
+
     pub fn get_unique_opponents(&self) -> u32 {
-        let mut opponent_count = 0;
-        for player in 0..self.player_count {
-            for ptr in self.players_played_with
-                [player * self.player_bit_word_count..(player + 1) * self.player_bit_word_count]
+        (0..self.player_count)
+            .map(|player| {
+                T::count_ones_in(BitSet::new(
+                    &self.players_played_with[player * self.player_bit_word_count..],
+                    self.player_count,
+                )) as u32
+            })
+            .sum()
+    }
+
+    /// Players `player` has met at a full table, in increasing order, which excludes the players
+    /// at the table being filled
+    pub fn get_opponents(&self, player: usize) -> Option<Vec<usize>> {
+        let played_with = self.get_played_with(player)?;
+        Some(
+            played_with
                 .iter()
-            {
-                opponent_count += ptr.count_ones();
-            }
+                .filter(|other| *other != player)
+                .collect(),
+        )
+    }
+
+    /// The players `player` has shared a full table with, as the search stores them
+    ///
+    /// Unlike `get_opponents`, this includes `player`, once they have sat at a full table.
+    pub fn get_played_with(&self, player: usize) -> Option<BitSet<'_, T>> {
+        if player >= self.player_count {
+            return None;
+        }
+        Some(BitSet::new(
+            &self.players_played_with[player * self.player_bit_word_count..],
+            self.player_count,
+        ))
+    }
+
+    /// Whether each pair of players has met at a full table, indexed by player then opponent
+    pub fn get_meetings(&self) -> Vec<Vec<bool>> {
+        (0..self.player_count)
+            .map(|player| {
+                let opponents = BitSet::new(
+                    &self.players_played_with[player * self.player_bit_word_count..],
+                    self.player_count,
+                );
+                (0..self.player_count)
+                    .map(|other| other != player && T::contains_in(opponents, other))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Players who have sat at `table` once it was full
+    pub fn get_table_visitors(&self, table: usize) -> Option<BitSet<'_, T>> {
+        if table >= self.groups.len() {
+            return None;
         }
-        opponent_count
+        Some(BitSet::new(
+            &self.played_on_table_total[table * self.player_bit_word_count..],
+            self.player_count,
+        ))
+    }
+
+    /// Tables `player` has sat at once they were full, in increasing order
+    pub fn get_visited_tables(&self, player: usize) -> Option<Vec<usize>> {
+        if player >= self.player_count {
+            return None;
+        }
+        Some(
+            (0..self.groups.len())
+                .filter(|table| {
+                    T::contains_in(
+                        BitSet::new(
+                            &self.played_on_table_total[table * self.player_bit_word_count..],
+                            self.player_count,
+                        ),
+                        player,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Players at the full tables of `round`, or `None` if the round has not started
+    pub fn get_round_occupancy(&self, round: usize) -> Option<BitSet<'_, T>> {
+        if round >= self.rounds || round > self.current_round {
+            return None;
+        }
+        Some(BitSet::new(
+            &self.played_in_round[round * self.player_bit_word_count..],
+            self.player_count,
+        ))
     }
 
     #[inline(always)]
@@ -226,117 +424,166 @@ impl<T: Word> DFScheduler<T> {
         }
     }
 
-    #[inline(always)]
-    fn attempt_forward(&mut self) -> Option<usize> {
-        let (min_player_byte, min_player_mask) = if let Some(min_player) = self.min_player {
-            let min_player_byte = min_player / T::SIZE;
-            let min_player_bit = T::ONE << (min_player - (min_player_byte * T::SIZE));
-            let min_player_mask = !((min_player_bit - T::ONE) | min_player_bit);
-            assert!(min_player_mask != T::MAX);
-            (Some(min_player_byte), min_player_mask)
+    /// Whether `player` has fewer unmet opponents, sharing an unvisited table with them, than
+    /// the rounds left before the round target need
+    ///
+    /// Each round visits a new table, so with a round for every unvisited table they need the
+    /// seats of all of them, and otherwise at least the seats of the smallest.
+    fn has_opponent_shortfall(&self, player: usize) -> bool {
+        let byte = player / T::SIZE;
+        let player_bit = T::ONE << (player - (byte * T::SIZE));
+        let mut unvisited = 0;
+        let mut unvisited_opponents = 0;
+        let mut smallest = usize::MAX;
+        for (table, size) in self.groups.iter().enumerate() {
+            if self.played_on_table_total[table * self.player_bit_word_count + byte] & player_bit
+                == T::ZERO
+            {
+                unvisited += 1;
+                unvisited_opponents += size.get() - 1;
+                smallest = smallest.min(size.get());
+            }
+        }
+        let rounds_left = self.rounds.saturating_sub(self.groups.len() - unvisited);
+        let needed = if rounds_left == unvisited {
+            unvisited_opponents
         } else {
-            (None, T::ZERO)
+            rounds_left * smallest.saturating_sub(1)
         };
-        'outer: for (i, mut temp) in self.temp_buffer.iter().cloned().enumerate() {
-            if let Some(min_player_byte) = min_player_byte {
-                if i < min_player_byte {
-                    continue;
-                }
-                if i == min_player_byte {
-                    temp &= min_player_mask;
+        let mut available = 0;
+        for i in 0..self.player_bit_word_count {
+            let mut pool = T::ZERO;
+            for table in 0..self.groups.len() {
+                let visited = &self.played_on_table_total[table * self.player_bit_word_count..]
+                    [..self.player_bit_word_count];
+                if visited[byte] & player_bit == T::ZERO {
+                    pool |= !visited[i];
                 }
             }
-            while temp != T::ZERO {
-                let trailing_zeros = temp.trailing_zeros() as usize;
-                let player = trailing_zeros + i * T::SIZE;
-                let player_bit = T::ONE << trailing_zeros;
-                temp &= !player_bit;
-                if player >= self.player_count {
-                    break 'outer;
-                }
-                debug_assert!(self
-                    .min_player
-                    .map(|min_player| player >= min_player)
-                    .unwrap_or(true));
-                self.on_current_table[self.on_current_table_offset + i] |= player_bit;
-
-                self.schedule.push(player);
-
-                self.current_position_in_table += 1;
-                if self.current_position_in_table >= self.groups[self.current_table].get() {
-                    let round_offset = self.current_round * self.player_bit_word_count;
-                    let table_offset = self.current_table * self.player_bit_word_count;
-                    for (i, block) in self.on_current_table[self.on_current_table_offset..]
-                        .iter()
-                        .enumerate()
-                    {
-                        self.played_in_round[round_offset + i] |= *block;
-                        self.played_on_table_total[table_offset + i] |= *block;
-                    }
-                    for player in
-                        self.schedule[self.schedule.len() - self.current_position_in_table..].iter()
-                    {
-                        for i in 0..self.player_bit_word_count {
-                            self.players_played_with[player * self.player_bit_word_count + i] |=
-                                self.on_current_table[self.on_current_table_offset + i];
-                        }
-                    }
+            pool &= !self.players_played_with[player * self.player_bit_word_count + i];
+            if i == byte {
+                pool &= !player_bit;
+            }
+            if i == self.player_bit_word_count - 1 && !self.player_count.is_multiple_of(T::SIZE) {
+                pool &= (T::ONE << (self.player_count % T::SIZE)) - T::ONE;
+            }
+            available += pool.count_ones() as usize;
+        }
+        needed > available
+    }
 
-                    self.min_player = None;
-
-                    self.current_table += 1;
-                    if self.current_table >= self.groups.len() {
-                        assert!(self.schedule.len() % self.player_count == 0);
-                        self.min_player =
-                            Some(self.schedule[self.schedule.len() - self.player_count]); // Each row must start with higher player than previous row
-                        self.current_table = 0;
-                        self.current_round += 1;
-                        self.played_in_round.resize(
-                            (self.current_round + 1) * self.player_bit_word_count,
-                            T::ZERO,
-                        );
-                    }
-                    self.current_position_in_table = 0;
+    #[inline(always)]
+    fn attempt_forward(&mut self) -> Option<usize> {
+        if self.is_complete() {
+            return None;
+        }
+        let start = self.min_player.map_or(0, |min_player| min_player + 1);
+        let player = T::first_set_in(BitSet::new(&self.temp_buffer, self.player_count), start)?;
+        Some(self.place(player))
+    }
 
-                    self.on_current_table_offset += self.player_bit_word_count;
-                    self.on_current_table.resize(
-                        self.on_current_table_offset + self.player_bit_word_count,
-                        T::ZERO,
-                    );
+    /// Seats `player`, which must be a candidate, returning the new length of the schedule
+    #[inline(always)]
+    fn place(&mut self, player: usize) -> usize {
+        T::insert_in(
+            &mut BitSetMut::new(
+                &mut self.on_current_table[self.on_current_table_offset..],
+                self.player_count,
+            ),
+            player,
+        );
 
-                    for (i, ptr) in self.temp_buffer.iter_mut().enumerate() {
-                        *ptr = !self.played_in_round
-                            [self.current_round * self.player_bit_word_count + i]
-                            & !self.played_on_table_total
-                                [self.current_table * self.player_bit_word_count + i];
-                    }
-                } else {
-                    self.min_player = Some(player);
-                    for (i, ptr) in self.temp_buffer.iter_mut().enumerate() {
-                        *ptr &= !self.players_played_with[player * self.player_bit_word_count + i];
-                    }
+        self.schedule.push(player);
+
+        self.current_position_in_table += 1;
+        if self.current_position_in_table >= self.groups[self.current_table].get() {
+            let round_offset = self.current_round * self.player_bit_word_count;
+            let table_offset = self.current_table * self.player_bit_word_count;
+            for (i, block) in self.on_current_table[self.on_current_table_offset..]
+                .iter()
+                .enumerate()
+            {
+                self.played_in_round[round_offset + i] |= *block;
+                self.played_on_table_total[table_offset + i] |= *block;
+            }
+            for player in
+                self.schedule[self.schedule.len() - self.current_position_in_table..].iter()
+            {
+                for i in 0..self.player_bit_word_count {
+                    self.players_played_with[player * self.player_bit_word_count + i] |=
+                        self.on_current_table[self.on_current_table_offset + i];
                 }
-                if self.schedule.len() > self.best_length {
-                    self.best_length = self.schedule.len();
+            }
+            let opponent_shortfall = self.schedule
+                [self.schedule.len() - self.current_position_in_table..]
+                .iter()
+                .any(|player| self.has_opponent_shortfall(*player));
+
+            self.min_player = None;
+
+            self.current_table += 1;
+            if self.current_table >= self.groups.len() {
+                assert!(self.schedule.len() % self.player_count == 0);
+                self.min_player = Some(self.schedule[self.schedule.len() - self.player_count]); // Each row must start with higher player than previous row
+                self.current_table = 0;
+                self.current_round += 1;
+                self.played_in_round.resize(
+                    (self.current_round + 1) * self.player_bit_word_count,
+                    T::ZERO,
+                );
+            }
+            self.current_position_in_table = 0;
+
+            self.on_current_table_offset += self.player_bit_word_count;
+            self.on_current_table.resize(
+                self.on_current_table_offset + self.player_bit_word_count,
+                T::ZERO,
+            );
+
+            for (i, ptr) in self.temp_buffer.iter_mut().enumerate() {
+                *ptr = !self.played_in_round[self.current_round * self.player_bit_word_count + i]
+                    & !self.played_on_table_total
+                        [self.current_table * self.player_bit_word_count + i];
+            }
+            if opponent_shortfall {
+                // Leave nothing to place, so that the next step backtracks
+                self.stats.contradictions.opponent_shortfall += 1;
+                self.pruned = true;
+                for ptr in self.temp_buffer.iter_mut() {
+                    *ptr = T::ZERO;
                 }
-                return Some(self.schedule.len());
             }
+        } else {
+            self.min_player = Some(player);
+            for (i, ptr) in self.temp_buffer.iter_mut().enumerate() {
+                *ptr &= !self.players_played_with[player * self.player_bit_word_count + i];
+            }
+        }
+        if self.schedule.len() > self.best_length {
+            self.best_length = self.schedule.len();
         }
-        None
+        self.schedule.len()
     }
 
     fn backtrack(&mut self) -> bool {
-        if self.schedule.len() < self.player_count {
+        if self.schedule.len() <= self.floor {
             return false;
         }
-        self.min_player = self.schedule.pop();
-
-        if let Some(player) = self.min_player {
-            let byte = player / T::SIZE;
-
-            let mask = !(T::ONE << (player - (byte * T::SIZE)));
+        self.min_player = self.unplace();
+        if self.min_player.is_some() {
+            self.generate_potential_players();
+            true
+        } else {
+            false
+        }
+    }
 
-            if self.current_position_in_table == 0 {
+    /// Removes the last seat, undoing its table, round and opponent bookkeeping
+    fn unplace(&mut self) -> Option<usize> {
+        let player = self.schedule.pop();
+        if let Some(player) = player {
+            let reopened = self.current_position_in_table == 0;
+            if reopened {
                 if self.current_table == 0 {
                     self.current_table = self.groups.len() - 1;
                     assert!(self.current_round != 0);
@@ -355,31 +602,523 @@ impl<T: Word> DFScheduler<T> {
                 self.current_position_in_table -= 1;
             }
 
-            self.played_in_round[self.current_round * self.player_bit_word_count + byte] &= mask;
-            self.on_current_table[self.on_current_table_offset + byte] &= mask;
-            self.played_on_table_total[self.current_table * self.player_bit_word_count + byte] &=
-                mask;
+            let word_count = self.player_bit_word_count;
+            let player_count = self.player_count;
+            let remove = |words: &mut [T], player| {
+                T::remove_in(&mut BitSetMut::new(words, player_count), player)
+            };
+            remove(
+                &mut self.played_in_round[self.current_round * word_count..],
+                player,
+            );
+            remove(
+                &mut self.on_current_table[self.on_current_table_offset..],
+                player,
+            );
+            remove(
+                &mut self.played_on_table_total[self.current_table * word_count..],
+                player,
+            );
 
             for other_player in
                 self.schedule[self.schedule.len() - self.current_position_in_table..].iter()
             {
-                let other_byte = other_player / T::SIZE;
-                self.players_played_with[player * self.player_bit_word_count + other_byte] &=
-                    !(T::ONE << (other_player - (other_byte * T::SIZE)));
-                self.players_played_with[other_player * self.player_bit_word_count + byte] &= mask;
+                remove(
+                    &mut self.players_played_with[player * word_count..],
+                    *other_player,
+                );
+                remove(
+                    &mut self.players_played_with[other_player * word_count..],
+                    player,
+                );
             }
 
-            self.generate_potential_players();
-            true
+            if reopened {
+                // The rest of the table has not sat together at a full table either, and in the
+                // first round it is their only table
+                let members =
+                    &self.schedule[self.schedule.len() - self.current_position_in_table..];
+                for (index, member) in members.iter().enumerate() {
+                    remove(
+                        &mut self.played_in_round[self.current_round * word_count..],
+                        *member,
+                    );
+                    remove(
+                        &mut self.played_on_table_total[self.current_table * word_count..],
+                        *member,
+                    );
+                    for other_member in members[index + 1..].iter() {
+                        remove(
+                            &mut self.players_played_with[member * word_count..],
+                            *other_member,
+                        );
+                        remove(
+                            &mut self.players_played_with[other_member * word_count..],
+                            *member,
+                        );
+                    }
+                    if self.current_round == 0 {
+                        remove(
+                            &mut self.players_played_with[member * word_count..],
+                            *member,
+                        );
+                    }
+                }
+                if self.current_round == 0 {
+                    remove(&mut self.players_played_with[player * word_count..], player);
+                }
+            }
+        }
+        player
+    }
+
+    /// Shortens the schedule to `len` seats, after which the search continues as it did when it
+    /// first reached that length, exploring every continuation of what is left before
+    /// backtracking further
+    ///
+    /// Panics if `len` is below `get_floor()`, as backtracking never goes there. `replay` a
+    /// shorter prefix to lower it.
+    pub fn truncate_to(&mut self, len: usize) {
+        assert!(
+            len >= self.floor,
+            "cannot truncate to {} seats, below the floor of {}",
+            len,
+            self.floor
+        );
+        self.rewind(len);
+    }
+
+    fn rewind(&mut self, len: usize) {
+        while self.schedule.len() > len {
+            self.unplace();
+        }
+        self.min_player = if self.current_position_in_table > 0 {
+            self.schedule.last().copied()
+        } else if self.current_table == 0 && self.current_round > 0 {
+            Some(self.schedule[self.schedule.len() - self.player_count])
         } else {
-            false
+            None
+        };
+        self.pruned = false;
+        self.generate_potential_players();
+    }
+
+    /// Replaces the schedule with `prefix` and makes it the floor, so that the search explores
+    /// only the continuations of `prefix`
+    ///
+    /// Each seat must be one the search could place: a player who is new to the table, has not
+    /// met anyone at it and is not already in the round. Players at a table are in increasing
+    /// order, as are the first players of each round. A prefix shorter than a round leaves the
+    /// rest of the first round to the search too. The scheduler is unchanged if the prefix is
+    /// rejected.
+    pub fn replay(&mut self, prefix: &[usize]) -> Result<(), ReplayError> {
+        let complete_length = self.get_complete_length();
+        if prefix.len() > complete_length {
+            return Err(ReplayError::TooLong {
+                length: prefix.len(),
+                complete_length,
+            });
+        }
+        let shared = self
+            .schedule
+            .iter()
+            .zip(prefix)
+            .take_while(|(seat, player)| seat == player)
+            .count();
+        let mut scheduler = self.clone();
+        scheduler.rewind(shared);
+        for (position, &player) in prefix.iter().enumerate().skip(shared) {
+            if player >= scheduler.player_count
+                || !T::contains_in(
+                    BitSet::new(&scheduler.temp_buffer, scheduler.player_count),
+                    player,
+                )
+            {
+                return Err(ReplayError::InvalidSeat { position, player });
+            }
+            if player < scheduler.min_player.map_or(0, |min_player| min_player + 1) {
+                return Err(ReplayError::OutOfOrder { position, player });
+            }
+            scheduler.place(player);
+            if scheduler.pruned {
+                return Err(ReplayError::OpponentShortfall { position });
+            }
         }
+        scheduler.floor = prefix.len();
+        *self = scheduler;
+        Ok(())
     }
 
+    /// Places the next player or backtracks, returning `None` once every option is exhausted
+    ///
+    /// After `DFStep::Complete` the search continues with the next complete schedule.
     #[inline(always)]
-    pub fn step(&mut self) -> Option<Option<usize>> {
-        self.attempt_forward()
-            .map(Some)
-            .or_else(|| if self.backtrack() { Some(None) } else { None })
+    pub fn step(&mut self) -> Option<DFStep> {
+        if let Some(length) = self.attempt_forward() {
+            self.stats.nodes += 1;
+            self.stats.max_depth = self.stats.max_depth.max(length);
+            return Some(if length == self.get_complete_length() {
+                DFStep::Complete
+            } else {
+                DFStep::Advanced(length)
+            });
+        }
+        // Leaving a complete schedule to find the next one is not a contradiction
+        let was_complete = self.is_complete();
+        if !self.backtrack() {
+            return None;
+        }
+        self.stats.backtracks += 1;
+        if self.pruned {
+            self.pruned = false;
+        } else if !was_complete {
+            self.stats.contradictions.no_candidates += 1;
+        }
+        Some(DFStep::Backtracked)
+    }
+
+    /// Steps through every complete schedule in turn
+    pub fn complete_schedules(&mut self) -> CompleteSchedules<'_, T> {
+        CompleteSchedules { scheduler: self }
+    }
+
+    /// Steps until the schedule reaches the round target, the search is exhausted or `budget`
+    /// runs out
+    ///
+    /// `best` is the longest schedule reached, and `score` is its length. It is a valid schedule
+    /// cut short, unlike one completed with `fill`, which does not check earlier opponents.
+    pub fn solve_with_budget(
+        &mut self,
+        budget: crate::budget::Budget,
+    ) -> crate::budget::BudgetedResult {
+        self.solve_cancellable(budget, &crate::cancel::CancellationToken::new())
+    }
+
+    /// As `solve_with_budget`, but also stops between steps once `cancel` is cancelled
+    pub fn solve_cancellable(
+        &mut self,
+        budget: crate::budget::Budget,
+        cancel: &crate::cancel::CancellationToken,
+    ) -> crate::budget::BudgetedResult {
+        use crate::budget::BudgetStatus;
+        let started = std::time::Instant::now();
+        let mut steps = 0;
+        let mut best = self.schedule.clone();
+        let status = loop {
+            if cancel.is_cancelled() {
+                break BudgetStatus::Cancelled;
+            }
+            if budget.is_spent(started, steps) {
+                break BudgetStatus::OutOfBudget;
+            }
+            steps += 1;
+            match self.step() {
+                Some(DFStep::Advanced(length)) => {
+                    if length > best.len() {
+                        best.clone_from(&self.schedule);
+                    }
+                }
+                Some(DFStep::Complete) => {
+                    best.clone_from(&self.schedule);
+                    break BudgetStatus::Complete;
+                }
+                Some(DFStep::Backtracked) => {}
+                None => break BudgetStatus::Exhausted,
+            }
+        };
+        self.stats.elapsed += started.elapsed();
+        crate::budget::BudgetedResult {
+            score: best.len(),
+            best,
+            status,
+            steps,
+            elapsed: started.elapsed(),
+            stats: self.stats,
+        }
+    }
+}
+
+impl<T: Word> Checkpoint for DFScheduler<T> {
+    fn save_checkpoint(&self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        let tables: Vec<usize> = self.groups.iter().map(|size| size.get()).collect();
+        checkpoint::write_header(writer, CheckpointKind::DFScheduler, T::SIZE, &tables)?;
+        checkpoint::write_usize(writer, self.rounds)?;
+        checkpoint::write_usizes(writer, &self.schedule)?;
+        checkpoint::write_usize(writer, self.best_length)?;
+        checkpoint::write_usize(writer, self.floor)?;
+        checkpoint::write_usize(writer, self.on_current_table_offset)?;
+        checkpoint::write_usize(writer, self.current_table)?;
+        checkpoint::write_usize(writer, self.current_position_in_table)?;
+        checkpoint::write_usize(writer, self.current_round)?;
+        match self.min_player {
+            None => checkpoint::write_usize(writer, 0)?,
+            Some(player) => {
+                checkpoint::write_usize(writer, 1)?;
+                checkpoint::write_usize(writer, player)?;
+            }
+        }
+        checkpoint::write_stats(writer, &self.stats)?;
+        checkpoint::write_usize(writer, self.pruned as usize)?;
+        write_words(writer, &self.players_played_with)?;
+        write_words(writer, &self.played_on_table_total)?;
+        write_words(writer, &self.played_in_round)?;
+        write_words(writer, &self.on_current_table)?;
+        write_words(writer, &self.temp_buffer)
+    }
+}
+
+/// Iterator from `DFScheduler::complete_schedules`, which leaves the scheduler where it stopped
+#[derive(Debug)]
+pub struct CompleteSchedules<'a, T: Word> {
+    scheduler: &'a mut DFScheduler<T>,
+}
+
+impl<'a, T: Word> Iterator for CompleteSchedules<'a, T> {
+    type Item = schedule_solver::Solution;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.scheduler.step()? == DFStep::Complete {
+                return Some(self.scheduler.get_solution());
+            }
+        }
+    }
+}
+
+/// Writes the length, then the `T::SIZE / 8` little endian bytes of each word
+fn write_words<T: Word>(writer: &mut dyn std::io::Write, words: &[T]) -> std::io::Result<()> {
+    checkpoint::write_usize(writer, words.len())?;
+    let mut bytes = vec![0; T::SIZE / 8];
+    for word in words.iter() {
+        word.write_le_bytes(&mut bytes);
+        writer.write_all(&bytes)?;
+    }
+    Ok(())
+}
+
+fn read_words<T: Word>(reader: &mut dyn std::io::Read) -> Result<Vec<T>, CheckpointError> {
+    let len = checkpoint::read_usize(reader)?;
+    let mut bytes = vec![0; T::SIZE / 8];
+    (0..len)
+        .map(|_| {
+            reader.read_exact(&mut bytes)?;
+            Ok(T::read_le_bytes(&bytes))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DFScheduler, ReplayError};
+    use crate::budget::{Budget, BudgetStatus};
+    use schedule_solver::{verify_seats, Constraints, Violation};
+    use std::num::NonZeroUsize;
+
+    fn groups(tables: &[usize]) -> Vec<NonZeroUsize> {
+        tables
+            .iter()
+            .map(|size| NonZeroUsize::new(*size).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn replay_keeps_the_prefix() {
+        let groups = groups(&[2; 4]);
+        let all: Vec<_> = DFScheduler::<u8>::new(&groups)
+            .with_rounds(3)
+            .complete_schedules()
+            .map(|solution| solution.get_seats().to_vec())
+            .collect();
+        let prefix = all[all.len() / 2][..16].to_vec();
+        let expected = all
+            .iter()
+            .filter(|seats| seats.starts_with(&prefix))
+            .count();
+        assert!(expected < all.len());
+
+        let mut scheduler = DFScheduler::<u8>::new(&groups).with_rounds(3);
+        scheduler.replay(&prefix).unwrap();
+        assert_eq!(scheduler.get_floor(), prefix.len());
+        let replayed: Vec<_> = scheduler
+            .complete_schedules()
+            .map(|solution| solution.get_seats().to_vec())
+            .collect();
+        assert_eq!(replayed.len(), expected);
+        assert!(replayed.iter().all(|seats| seats.starts_with(&prefix)));
+    }
+
+    #[test]
+    fn replay_rejects_seats_out_of_order() {
+        let mut scheduler = DFScheduler::<u8>::new(&groups(&[2; 4]));
+        assert_eq!(
+            scheduler.replay(&[1, 0]),
+            Err(ReplayError::OutOfOrder {
+                position: 1,
+                player: 0
+            })
+        );
+        assert_eq!(
+            scheduler.replay(&[0, 1, 2, 3, 4, 5, 6, 7, 0]),
+            Err(ReplayError::InvalidSeat {
+                position: 8,
+                player: 0
+            })
+        );
+        assert!(scheduler.get_schedule().is_empty());
+    }
+
+    #[test]
+    fn backtracking_from_complete_is_no_contradiction() {
+        let mut scheduler = DFScheduler::<u8>::new(&groups(&[2; 4])).with_rounds(3);
+        while scheduler.step() != Some(super::DFStep::Complete) {}
+        let stats = *scheduler.get_stats();
+        assert_eq!(scheduler.step(), Some(super::DFStep::Backtracked));
+        assert_eq!(scheduler.get_stats().contradictions, stats.contradictions);
+        assert_eq!(scheduler.get_stats().backtracks, stats.backtracks + 1);
+    }
+
+    /// Checks the history accessors against the full tables of the seat list
+    fn assert_history_matches_seats(scheduler: &DFScheduler<u8>, tables: &[usize]) {
+        let player_count: usize = tables.iter().sum();
+        let mut met = vec![vec![false; player_count]; player_count];
+        let mut visited = vec![Vec::new(); player_count];
+        let mut occupancy = vec![Vec::new(); scheduler.get_rounds()];
+        let mut full_tables = scheduler
+            .get_schedule()
+            .chunks(player_count)
+            .enumerate()
+            .flat_map(|(round, seats)| {
+                let mut start = 0;
+                tables.iter().enumerate().filter_map(move |(table, size)| {
+                    let range = start..start + size;
+                    start += size;
+                    let players = seats.get(range)?;
+                    Some((round, table, players))
+                })
+            });
+        for (round, table, players) in &mut full_tables {
+            for player in players {
+                met[*player]
+                    .iter_mut()
+                    .zip(0..)
+                    .filter(|(_, other)| players.contains(other) && other != player)
+                    .for_each(|(met, _)| *met = true);
+                visited[*player].push(table);
+                occupancy[round].push(*player);
+            }
+        }
+
+        assert_eq!(scheduler.get_meetings(), met);
+        for player in 0..player_count {
+            let mut tables_visited = visited[player].clone();
+            tables_visited.sort_unstable();
+            assert_eq!(scheduler.get_visited_tables(player), Some(tables_visited));
+            let opponents = scheduler.get_opponents(player).unwrap();
+            assert!(!opponents.contains(&player));
+            let expected: Vec<_> = (0..player_count)
+                .filter(|other| met[player][*other])
+                .collect();
+            assert_eq!(opponents, expected);
+            let played_with = scheduler.get_played_with(player).unwrap();
+            assert_eq!(played_with.contains(player), !visited[player].is_empty());
+        }
+        for (round, players) in occupancy.iter_mut().enumerate() {
+            players.sort_unstable();
+            if let Some(occupied) = scheduler.get_round_occupancy(round) {
+                assert_eq!(&occupied.iter().collect::<Vec<_>>(), players);
+            } else {
+                assert!(players.is_empty());
+            }
+        }
+        assert!(scheduler.get_opponents(player_count).is_none());
+        assert!(scheduler.get_played_with(player_count).is_none());
+        assert!(scheduler.get_visited_tables(player_count).is_none());
+        assert!(scheduler.get_table_visitors(tables.len()).is_none());
+        assert!(scheduler
+            .get_round_occupancy(scheduler.get_rounds())
+            .is_none());
+    }
+
+    #[test]
+    fn history_counts_only_full_tables() {
+        let tables = [3, 3, 2, 3];
+        let mut scheduler = DFScheduler::<u8>::new(&groups(&tables));
+        let mut backtracks = 0;
+        for _ in 0..3000 {
+            match scheduler.step() {
+                Some(super::DFStep::Backtracked) => backtracks += 1,
+                Some(_) => {}
+                None => break,
+            }
+            assert_history_matches_seats(&scheduler, &tables);
+        }
+        assert!(backtracks > 0);
+        let prefix = scheduler.get_schedule()[..4].to_vec();
+        scheduler.replay(&prefix).unwrap();
+        assert_history_matches_seats(&scheduler, &tables);
+    }
+
+    /// The same search with 256 bit words, on layouts within one word and across several
+    #[cfg(feature = "simd")]
+    #[test]
+    fn simd_words_match_u64() {
+        use schedule_solver::SimdWord256;
+
+        let small = groups(&[2; 4]);
+        let simd: Vec<_> = DFScheduler::<SimdWord256>::new(&small)
+            .with_rounds(3)
+            .complete_schedules()
+            .map(|solution| solution.get_seats().to_vec())
+            .collect();
+        let plain: Vec<_> = DFScheduler::<u64>::new(&small)
+            .with_rounds(3)
+            .complete_schedules()
+            .map(|solution| solution.get_seats().to_vec())
+            .collect();
+        assert!(!plain.is_empty());
+        assert_eq!(simd, plain);
+
+        // 300 players, so each player set spans two words
+        let large = groups(&[4; 75]);
+        let mut simd = DFScheduler::<SimdWord256>::new(&large).with_rounds(2);
+        let mut plain = DFScheduler::<u64>::new(&large).with_rounds(2);
+        loop {
+            let step = plain.step();
+            assert_eq!(simd.step(), step);
+            assert_eq!(simd.get_schedule(), plain.get_schedule());
+            if matches!(step, None | Some(super::DFStep::Complete)) {
+                break;
+            }
+        }
+        assert!(plain.is_complete());
+    }
+
+    /// A budget too small to finish, so `best` is the longest schedule reached
+    #[test]
+    fn budgeted_best_is_valid() {
+        let tables = [3, 3, 2, 3];
+        let mut scheduler = DFScheduler::<u16>::new(&groups(&tables));
+        let result = scheduler.solve_with_budget(Budget::steps(500));
+        assert_eq!(result.status, BudgetStatus::OutOfBudget);
+        assert_eq!(result.score, result.best.len());
+        let violations: Vec<_> = verify_seats(&tables, &result.best, &Constraints::default())
+            .into_iter()
+            .filter(|violation| {
+                // The last round is cut short
+                !matches!(
+                    violation,
+                    Violation::TableSize { .. } | Violation::MissingPlayer { .. }
+                )
+            })
+            .collect();
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+
+    #[test]
+    #[should_panic]
+    fn truncate_below_the_first_round_panics() {
+        let mut scheduler = DFScheduler::<u8>::new(&groups(&[2; 4]));
+        scheduler.fill();
+        scheduler.truncate_to(5);
     }
 }
@@ -1,3 +1,9 @@
 #![feature(const_mut_refs)]
 
+pub mod budget;
+pub mod cancel;
+pub mod checkpoint;
 pub mod df_schedule;
+pub mod observer;
+pub mod search;
+pub mod solver;
@@ -24,11 +24,59 @@ pub fn print_schedule(schedule: &[usize], groups: &[std::num::NonZeroUsize]) ->
     output
 }
 
+struct PrintObserver {
+    checkpointer: Option<df_social_schedule::checkpoint::Checkpointer>,
+}
+
+impl df_social_schedule::observer::SearchObserver for PrintObserver {
+    fn on_new_best(&mut self, search: &df_social_schedule::search::Search<'_>) {
+        let scheduler = search.get_scheduler();
+        println!(
+            "New best: {:?} with depth {} step {} empty tables {}",
+            scheduler.get_players_placed(search.get_current()),
+            search.get_depth(),
+            search.get_steps(),
+            scheduler.get_empty_table_count(search.get_current())
+        );
+        let mut output = String::new();
+        scheduler
+            .format_schedule(search.get_current(), &mut output)
+            .unwrap();
+        println!("Schedule:\n{}", output);
+    }
+
+    fn on_progress(
+        &mut self,
+        search: &df_social_schedule::search::Search<'_>,
+        progress: &df_social_schedule::observer::Progress,
+    ) {
+        println!(
+            "Current depth {} (recent min {} max {}) (best players_placed {} lowest empty_tables {}) with rate {}/s {} total",
+            progress.depth,
+            progress.recent_min_depth,
+            progress.recent_max_depth,
+            progress.best_players_placed,
+            progress.lowest_empty_tables,
+            progress.rate,
+            progress.steps
+        );
+        println!("{:?}", search.get_stats());
+        if let Some(checkpointer) = self.checkpointer.as_mut() {
+            if let Err(error) = checkpointer.save_if_due(search) {
+                println!("Could not save checkpoint: {}", error);
+            }
+        }
+    }
+}
+
 fn main() {
     const GROUPS: &[usize] = &[4; 6];
 
     const SCHEDULER: schedule_solver::Schedule =
-        schedule_solver::Schedule::new(GROUPS, GROUPS.len());
+        match schedule_solver::ScheduleBuilder::new(GROUPS).build() {
+            Ok(scheduler) => scheduler,
+            Err(_) => panic!("Invalid tables"),
+        };
     const BUF: [usize; SCHEDULER.get_block_size()] = {
         let mut buf = [0; SCHEDULER.get_block_size()];
         if !SCHEDULER.initialise_buffer(&mut buf) {
@@ -41,103 +89,97 @@ fn main() {
 
     let mut buf = BUF.to_vec();
 
-    let mut current_depth = 0;
-
-    let mut total_step_count: usize = 0;
-    let mut highest_players_placed = 0;
-    let mut lowest_empty_tables = usize::MAX;
-    let mut check_frequency = 1;
-    let mut check_counter = 0;
-    let mut i = 0;
-    let mut last_print = std::time::Instant::now();
-
-    let mut recent_depth_stats = (0, usize::MAX);
-    loop {
-        {
-            let target_size = (current_depth + 2) * SCHEDULER.get_block_size();
-            if target_size > buf.len() {
-                buf.resize(target_size, 0);
-            }
-            let buffer: &mut [usize] = &mut buf[current_depth * SCHEDULER.get_block_size()..];
-            let (buf_1, buf_2) = buffer.split_at_mut(SCHEDULER.get_block_size());
-            if let Some(finished) = SCHEDULER.step(buf_1, buf_2) {
-                if finished {
-                    assert_eq!(SCHEDULER.get_players_placed(buf_1), 4 * 6 * 6);
-                    assert_eq!(SCHEDULER.get_empty_table_count(buf_1), 0);
-                    println!("Found a solution: {:?}", buf_1);
-                    //current_depth -= 1;
+    if let Some(construction) = SCHEDULER.construct(&mut buf) {
+        let mut output = String::new();
+        SCHEDULER.format_schedule(&buf, &mut output).unwrap();
+        println!("Constructed from {:?}:\n{}", construction, output);
+        return;
+    }
+
+    // Pass a file path to save the search every minute, and to resume from it on the next run
+    let checkpointer = std::env::args().nth(1).map(|path| {
+        df_social_schedule::checkpoint::Checkpointer::new(path, std::time::Duration::from_secs(60))
+    });
+    let resumed = match checkpointer
+        .as_ref()
+        .map(|checkpointer| checkpointer.open())
+    {
+        Some(Ok(Some(mut reader))) => {
+            match df_social_schedule::search::Search::resume(SCHEDULER, &mut reader) {
+                Ok(search) => Some(search),
+                Err(error) => {
+                    println!("Could not resume from checkpoint: {}", error);
                     return;
-                } else {
-                    assert_ne!(buf_1, buf_2);
-                    assert!(current_depth <= SCHEDULER.get_players_placed(buf_2));
-                    current_depth += 1;
                 }
-            } else {
-                current_depth = current_depth.checked_sub(1).expect("No valid schedule");
             }
         }
-        total_step_count += 1;
-
-        {
-            let players_placed =
-                SCHEDULER.get_players_placed(&buf[current_depth * SCHEDULER.get_block_size()..]);
-            let empty_tables =
-                SCHEDULER.get_empty_table_count(&buf[current_depth * SCHEDULER.get_block_size()..]);
-            let mut should_print = false;
-            if players_placed >= highest_players_placed {
-                highest_players_placed = players_placed;
-                should_print = true;
-            }
-            if empty_tables < lowest_empty_tables {
-                lowest_empty_tables = empty_tables;
-                should_print = true;
-            }
-            if should_print {
-                println!(
-                    "New best: {:?} with depth {} step {} empty tables {}",
-                    players_placed, current_depth, total_step_count, empty_tables
-                );
-                let mut output = String::new();
-                SCHEDULER
-                    .format_schedule(
-                        &buf[current_depth * SCHEDULER.get_block_size()..],
-                        &mut output,
-                    )
-                    .unwrap();
-                println!("Schedule:\n{}", output);
-            }
-
-            if current_depth > recent_depth_stats.0 {
-                recent_depth_stats.0 = current_depth;
+        Some(Err(error)) => {
+            println!("Could not open checkpoint: {}", error);
+            return;
+        }
+        _ => None,
+    };
+    let mut search = match resumed {
+        Some(search) => {
+            println!("Resumed at step {}", search.get_steps());
+            search
+        }
+        None => match df_social_schedule::search::Search::new(SCHEDULER) {
+            Ok(search) => search,
+            Err(reason) => {
+                println!("No valid schedule: {}", reason);
+                return;
             }
+        },
+    };
 
-            if current_depth < recent_depth_stats.1 {
-                recent_depth_stats.1 = current_depth;
+    // Pressing enter stops the search, keeping the latest checkpoint
+    let cancel = df_social_schedule::cancel::CancellationToken::new();
+    {
+        let cancel = cancel.clone();
+        std::thread::spawn(move || {
+            let mut line = String::new();
+            if std::io::stdin().read_line(&mut line).is_ok() {
+                cancel.cancel();
             }
+        });
+    }
 
-            i += 1;
-            check_counter += 1;
-            if check_counter > check_frequency {
-                check_counter = 0;
-                if last_print.elapsed().as_millis() > 400 {
-                    println!(
-                    "Current depth {} (recent min {} max {}) (best players_placed {} lowest empty_tables {}) with rate {}/s {} total",
-                    current_depth,
-					recent_depth_stats.1,
-					recent_depth_stats.0,
-                    highest_players_placed,
-					lowest_empty_tables,
-                    i as f64 / last_print.elapsed().as_secs_f64(),
-					total_step_count
-                );
-
-                    recent_depth_stats = (0, usize::MAX);
-
-                    last_print = std::time::Instant::now();
-                    check_frequency = (i / 16).max(1);
-                    i = 0;
+    let mut observer = PrintObserver { checkpointer };
+    match search.solve_with_observer(&mut observer, &cancel) {
+        Ok(None) => {
+            println!(
+                "Cancelled at depth {} after {} steps ({:?})",
+                search.get_depth(),
+                search.get_steps(),
+                search.get_stats()
+            );
+            if let Some(checkpointer) = observer.checkpointer.as_mut() {
+                if let Err(error) = checkpointer.save(&search) {
+                    println!("Could not save checkpoint: {}", error);
                 }
             }
+            return;
+        }
+        Ok(Some(solution)) => {
+            assert_eq!(SCHEDULER.get_players_placed(solution), 4 * 6 * 6);
+            assert_eq!(SCHEDULER.get_empty_table_count(solution), 0);
+            let solution = SCHEDULER.to_solution(solution).unwrap();
+            println!("Found a solution ({:?}):", search.get_stats());
+            let violations = schedule_solver::verify_seats(
+                GROUPS,
+                solution.get_seats(),
+                &schedule_solver::Constraints::default(),
+            );
+            assert!(violations.is_empty(), "Invalid solution: {:?}", violations);
+            for round in solution.rounds() {
+                println!("{:?}", round.tables().collect::<Vec<_>>());
+            }
+            return;
+        }
+        Err(reason) => {
+            println!("No valid schedule: {}", reason);
+            return;
         }
     }
 
@@ -152,14 +194,13 @@ fn main() {
 
     let ops = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
     let best_counter = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
-    let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
     let best_string = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
     let current_string = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
 
     let output_thread = {
         let ops = ops.clone();
         let best_counter = best_counter.clone();
-        let running = running.clone();
+        let cancel = cancel.clone();
         let best_string = best_string.clone();
         let current_string = current_string.clone();
         let groups = groups.clone();
@@ -178,13 +219,23 @@ fn main() {
                     best_counter.load(std::sync::atomic::Ordering::Relaxed) as f32
                         / now.elapsed().as_secs_f32()
                 );
-                should_continue = running.load(std::sync::atomic::Ordering::Relaxed);
+                should_continue = !cancel.is_cancelled();
             }
         })
     };
     let mut local_ops = 0;
     let mut cloned_scheduler = scheduler.clone();
-    while let Some(size) = scheduler.step() {
+    while let Some(step) = scheduler.step() {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let size = match step {
+            df_social_schedule::df_schedule::DFStep::Advanced(size) => Some(size),
+            df_social_schedule::df_schedule::DFStep::Complete => {
+                Some(scheduler.get_complete_length())
+            }
+            df_social_schedule::df_schedule::DFStep::Backtracked => None,
+        };
         if let Some(size) = size {
             local_ops += 1;
             if local_ops > 100_000 {
@@ -218,6 +269,6 @@ fn main() {
             }
         }
     }
-    running.store(false, std::sync::atomic::Ordering::Relaxed);
+    cancel.cancel();
     let _ = output_thread.join();
 }
@@ -0,0 +1,160 @@
+use crate::search::Search;
+use std::time::Duration;
+
+/// Statistics reported periodically while searching
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Progress {
+    pub steps: usize,
+    pub depth: usize,
+    /// Shallowest depth since the last report
+    pub recent_min_depth: usize,
+    /// Deepest depth since the last report
+    pub recent_max_depth: usize,
+    pub best_players_placed: usize,
+    pub lowest_empty_tables: usize,
+    /// Steps per second since the last report
+    pub rate: f64,
+    pub elapsed: Duration,
+}
+
+/// Callbacks from `Search::solve_with_observer`, which all default to doing nothing
+pub trait SearchObserver {
+    /// How often `on_progress` is called
+    fn progress_interval(&self) -> Duration {
+        Duration::from_millis(400)
+    }
+
+    /// The current block has placed more players, or left fewer empty tables, than any before it
+    fn on_new_best(&mut self, _search: &Search<'_>) {}
+
+    /// The search backtracked to `search.get_depth()`
+    fn on_backtrack(&mut self, _search: &Search<'_>) {}
+
+    fn on_progress(&mut self, _search: &Search<'_>, _progress: &Progress) {}
+
+    /// The current block is a complete schedule
+    fn on_solution(&mut self, _search: &Search<'_>) {}
+}
+
+impl SearchObserver for () {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Progress, SearchObserver};
+    use crate::cancel::CancellationToken;
+    use crate::search::Search;
+    use schedule_solver::ScheduleBuilder;
+    use std::time::Duration;
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        NewBest {
+            players_placed: usize,
+            empty_tables: usize,
+        },
+        Backtrack {
+            depth: usize,
+        },
+        Progress(Progress),
+        Solution,
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<Event>,
+    }
+
+    impl SearchObserver for Recorder {
+        /// Report on every chance, which is every `TIME_CHECK_INTERVAL` steps
+        fn progress_interval(&self) -> Duration {
+            Duration::ZERO
+        }
+
+        fn on_new_best(&mut self, search: &Search<'_>) {
+            let schedule = search.get_scheduler();
+            self.events.push(Event::NewBest {
+                players_placed: schedule.get_players_placed(search.get_current()),
+                empty_tables: schedule.get_empty_table_count(search.get_current()),
+            });
+        }
+
+        fn on_backtrack(&mut self, search: &Search<'_>) {
+            self.events.push(Event::Backtrack {
+                depth: search.get_depth(),
+            });
+        }
+
+        fn on_progress(&mut self, _search: &Search<'_>, progress: &Progress) {
+            self.events.push(Event::Progress(*progress));
+        }
+
+        fn on_solution(&mut self, _search: &Search<'_>) {
+            self.events.push(Event::Solution);
+        }
+    }
+
+    #[test]
+    fn callbacks_match_the_stats() {
+        let tables = [3; 5];
+        let schedule = ScheduleBuilder::new(&tables).rounds(3).build().unwrap();
+        let mut search = Search::new(schedule).unwrap();
+        let start = search.get_current().to_vec();
+        let mut recorder = Recorder::default();
+        let solved = search
+            .solve_with_observer(&mut recorder, &CancellationToken::new())
+            .unwrap()
+            .is_some();
+        assert!(solved);
+        let stats = *search.get_stats();
+        let steps = search.get_steps();
+        let events = recorder.events;
+
+        assert_eq!(events.last(), Some(&Event::Solution));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| **event == Event::Solution)
+                .count(),
+            1
+        );
+        assert!(stats.backtracks > 0);
+        assert!(steps > crate::budget::TIME_CHECK_INTERVAL);
+        assert_eq!(stats.nodes + stats.backtracks + 1, steps);
+        let backtracks = events
+            .iter()
+            .filter(|event| matches!(event, Event::Backtrack { .. }))
+            .count();
+        assert_eq!(backtracks, stats.backtracks);
+
+        let schedule = search.get_scheduler();
+        let mut best = (
+            schedule.get_players_placed(&start),
+            schedule.get_empty_table_count(&start),
+        );
+        let mut reports = Vec::new();
+        for event in &events {
+            match event {
+                Event::NewBest {
+                    players_placed,
+                    empty_tables,
+                } => {
+                    assert!(*players_placed > best.0 || *empty_tables < best.1);
+                    best = (best.0.max(*players_placed), best.1.min(*empty_tables));
+                }
+                Event::Backtrack { depth } => assert!(*depth < stats.max_depth),
+                Event::Progress(progress) => {
+                    assert!(progress.recent_min_depth <= progress.depth);
+                    assert!(progress.depth <= progress.recent_max_depth);
+                    assert_eq!(progress.best_players_placed, best.0);
+                    assert_eq!(progress.lowest_empty_tables, best.1);
+                    reports.push(progress.steps);
+                }
+                Event::Solution => {}
+            }
+        }
+        let expected: Vec<usize> = (1..=(steps - 1) / crate::budget::TIME_CHECK_INTERVAL)
+            .map(|report| report * crate::budget::TIME_CHECK_INTERVAL)
+            .collect();
+        assert_eq!(reports, expected);
+    }
+}
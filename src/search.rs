@@ -0,0 +1,311 @@
+use crate::budget::{Budget, BudgetStatus, BudgetedResult};
+use crate::cancel::CancellationToken;
+use crate::checkpoint::{self, Checkpoint, CheckpointError, CheckpointKind};
+use crate::observer::{Progress, SearchObserver};
+use schedule_solver::{Infeasibility, Schedule, SearchStats};
+
+pub use schedule_solver::SearchStep;
+
+/// Depth first search over a stack of `Schedule` blocks
+#[derive(Debug)]
+pub struct Search<'a> {
+    schedule: Schedule<'a>,
+    buffer: Vec<usize>,
+    depth: usize,
+    steps: usize,
+    stats: SearchStats,
+}
+
+impl<'a> Search<'a> {
+    pub fn new(schedule: Schedule<'a>) -> Result<Self, Infeasibility> {
+        schedule.check_feasibility()?;
+        let mut buffer = vec![0; schedule.get_block_size()];
+        schedule
+            .try_initialise_buffer(&mut buffer)
+            .expect("Schedule has no rounds");
+        Ok(Self {
+            schedule,
+            buffer,
+            depth: 0,
+            steps: 0,
+            stats: SearchStats::default(),
+        })
+    }
+
+    /// Continues a search saved with `save_checkpoint`, which must have used the same tables and
+    /// rounds as `schedule`
+    pub fn resume(
+        schedule: Schedule<'a>,
+        reader: &mut dyn std::io::Read,
+    ) -> Result<Self, CheckpointError> {
+        checkpoint::read_header(
+            reader,
+            CheckpointKind::Search,
+            usize::BITS as usize,
+            schedule.get_tables(),
+        )?;
+        let rounds = checkpoint::read_usize(reader)?;
+        if rounds != schedule.get_rounds() {
+            return Err(CheckpointError::RoundsMismatch {
+                expected: schedule.get_rounds(),
+                found: rounds,
+            });
+        }
+        let depth = checkpoint::read_usize(reader)?;
+        let steps = checkpoint::read_usize(reader)?;
+        let stats = checkpoint::read_stats(reader)?;
+        let buffer = checkpoint::read_usizes(reader)?;
+        if Some(buffer.len())
+            != depth
+                .checked_add(1)
+                .map(|blocks| blocks * schedule.get_block_size())
+        {
+            return Err(CheckpointError::Corrupt);
+        }
+        Ok(Self {
+            schedule,
+            buffer,
+            depth,
+            steps,
+            stats,
+        })
+    }
+
+    pub fn get_scheduler(&self) -> &Schedule<'a> {
+        &self.schedule
+    }
+
+    pub fn get_depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn get_steps(&self) -> usize {
+        self.steps
+    }
+
+    /// `elapsed` covers time spent in the `solve` methods, not in calls to `step`
+    pub fn get_stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    /// The block at the current depth
+    pub fn get_current(&self) -> &[usize] {
+        &self.buffer[self.depth * self.schedule.get_block_size()..]
+            [..self.schedule.get_block_size()]
+    }
+
+    pub fn step(&mut self) -> Result<SearchStep, Infeasibility> {
+        let block_size = self.schedule.get_block_size();
+        let target_size = (self.depth + 2) * block_size;
+        if target_size > self.buffer.len() {
+            self.buffer.resize(target_size, 0);
+        }
+        let (buf_1, buf_2) = self.buffer[self.depth * block_size..].split_at_mut(block_size);
+        let result = match self.schedule.step_with_stats(buf_1, buf_2, &mut self.stats) {
+            Some(true) => SearchStep::Solved,
+            Some(false) => {
+                debug_assert!(self.depth <= self.schedule.get_players_placed(buf_2));
+                self.depth += 1;
+                self.stats.max_depth = self.stats.max_depth.max(self.depth);
+                SearchStep::Advanced
+            }
+            None => {
+                self.depth = self
+                    .depth
+                    .checked_sub(1)
+                    .ok_or(Infeasibility::SearchExhausted { steps: self.steps })?;
+                SearchStep::Backtracked
+            }
+        };
+        self.steps += 1;
+        Ok(result)
+    }
+
+    /// Steps until a schedule is found
+    pub fn solve(&mut self) -> Result<&[usize], Infeasibility> {
+        let started = std::time::Instant::now();
+        let result = loop {
+            match self.step() {
+                Ok(SearchStep::Solved) => break Ok(()),
+                Ok(_) => {}
+                Err(reason) => break Err(reason),
+            }
+        };
+        self.stats.elapsed += started.elapsed();
+        result.map(move |()| self.get_current())
+    }
+
+    /// Steps until a schedule is found or `cancel` is cancelled, reporting to `observer` along
+    /// the way
+    ///
+    /// Returns `Ok(None)` when cancelled, leaving the search where it stopped.
+    pub fn solve_with_observer(
+        &mut self,
+        observer: &mut dyn SearchObserver,
+        cancel: &CancellationToken,
+    ) -> Result<Option<&[usize]>, Infeasibility> {
+        let started = std::time::Instant::now();
+        let result = self.run_observed(observer, cancel, started);
+        self.stats.elapsed += started.elapsed();
+        Ok(if result? {
+            Some(self.get_current())
+        } else {
+            None
+        })
+    }
+
+    /// Returns whether a schedule was found
+    fn run_observed(
+        &mut self,
+        observer: &mut dyn SearchObserver,
+        cancel: &CancellationToken,
+        started: std::time::Instant,
+    ) -> Result<bool, Infeasibility> {
+        let mut last_progress = started;
+        let mut steps_since_progress: usize = 0;
+        let mut best_players_placed = self.schedule.get_players_placed(self.get_current());
+        let mut lowest_empty_tables = self.schedule.get_empty_table_count(self.get_current());
+        let mut recent_depths = (self.depth, self.depth);
+        loop {
+            if cancel.is_cancelled() {
+                return Ok(false);
+            }
+            let step = self.step()?;
+            steps_since_progress += 1;
+            match step {
+                SearchStep::Solved => {
+                    observer.on_solution(self);
+                    return Ok(true);
+                }
+                SearchStep::Backtracked => observer.on_backtrack(self),
+                SearchStep::Advanced => {}
+            }
+
+            let players_placed = self.schedule.get_players_placed(self.get_current());
+            let empty_tables = self.schedule.get_empty_table_count(self.get_current());
+            if players_placed > best_players_placed || empty_tables < lowest_empty_tables {
+                best_players_placed = best_players_placed.max(players_placed);
+                lowest_empty_tables = lowest_empty_tables.min(empty_tables);
+                observer.on_new_best(self);
+            }
+            recent_depths = (
+                recent_depths.0.min(self.depth),
+                recent_depths.1.max(self.depth),
+            );
+
+            if steps_since_progress.is_multiple_of(crate::budget::TIME_CHECK_INTERVAL)
+                && last_progress.elapsed() >= observer.progress_interval()
+            {
+                let progress = Progress {
+                    steps: self.steps,
+                    depth: self.depth,
+                    recent_min_depth: recent_depths.0,
+                    recent_max_depth: recent_depths.1,
+                    best_players_placed,
+                    lowest_empty_tables,
+                    rate: steps_since_progress as f64 / last_progress.elapsed().as_secs_f64(),
+                    elapsed: started.elapsed(),
+                };
+                observer.on_progress(self, &progress);
+                last_progress = std::time::Instant::now();
+                steps_since_progress = 0;
+                recent_depths = (self.depth, self.depth);
+            }
+        }
+    }
+
+    /// Searches until a schedule is found, every option is exhausted or `budget` runs out
+    ///
+    /// `best` is the block with the most players placed, then the fewest empty tables, and
+    /// `score` is its number of players placed. The search can be continued afterwards.
+    pub fn solve_with_budget(&mut self, budget: Budget) -> BudgetedResult {
+        self.solve_cancellable(budget, &CancellationToken::new())
+    }
+
+    /// As `solve_with_budget`, but also stops between steps once `cancel` is cancelled
+    pub fn solve_cancellable(
+        &mut self,
+        budget: Budget,
+        cancel: &CancellationToken,
+    ) -> BudgetedResult {
+        let started = std::time::Instant::now();
+        let mut steps = 0;
+        let mut best = self.get_current().to_vec();
+        let status = loop {
+            if cancel.is_cancelled() {
+                break BudgetStatus::Cancelled;
+            }
+            if budget.is_spent(started, steps) {
+                break BudgetStatus::OutOfBudget;
+            }
+            let step = self.step();
+            steps += 1;
+            match step {
+                Ok(SearchStep::Solved) => {
+                    best.copy_from_slice(self.get_current());
+                    break BudgetStatus::Complete;
+                }
+                Ok(_) => {
+                    let current = self.get_current();
+                    let players_placed = self.schedule.get_players_placed(current);
+                    let best_players_placed = self.schedule.get_players_placed(&best);
+                    if players_placed > best_players_placed
+                        || (players_placed == best_players_placed
+                            && self.schedule.get_empty_table_count(current)
+                                < self.schedule.get_empty_table_count(&best))
+                    {
+                        best.copy_from_slice(current);
+                    }
+                }
+                Err(_) => break BudgetStatus::Exhausted,
+            }
+        };
+        self.stats.elapsed += started.elapsed();
+        BudgetedResult {
+            score: self.schedule.get_players_placed(&best),
+            best,
+            status,
+            steps,
+            elapsed: started.elapsed(),
+            stats: self.stats,
+        }
+    }
+}
+
+impl<'a> Checkpoint for Search<'a> {
+    /// Saves the blocks up to the current depth, which is all the search needs to continue
+    fn save_checkpoint(&self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        checkpoint::write_header(
+            writer,
+            CheckpointKind::Search,
+            usize::BITS as usize,
+            self.schedule.get_tables(),
+        )?;
+        checkpoint::write_usize(writer, self.schedule.get_rounds())?;
+        checkpoint::write_usize(writer, self.depth)?;
+        checkpoint::write_usize(writer, self.steps)?;
+        checkpoint::write_stats(writer, &self.stats)?;
+        checkpoint::write_usizes(
+            writer,
+            &self.buffer[..(self.depth + 1) * self.schedule.get_block_size()],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Search;
+    use schedule_solver::{verify_seats, Constraints, ScheduleBuilder};
+
+    /// More players than a `usize` holds, so every player set spans several words
+    #[test]
+    fn solves_multi_word_layout() {
+        let tables = [4; 17];
+        let schedule = ScheduleBuilder::new(&tables).rounds(3).build().unwrap();
+        let mut search = Search::new(schedule).unwrap();
+        let block = search.solve().unwrap().to_vec();
+        let solution = search.get_scheduler().to_solution(&block).unwrap();
+        let violations = verify_seats(&tables, solution.get_seats(), &Constraints::default());
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+}
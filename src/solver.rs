@@ -0,0 +1,140 @@
+use crate::budget::{Budget, BudgetedResult};
+use crate::df_schedule::{DFScheduler, DFStep, Word};
+use crate::search::{Search, SearchStep};
+use schedule_solver::{Infeasibility, ScheduleBuilder, ScheduleErrors, SearchStats, Solution};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConfigureError {
+    Schedule(ScheduleErrors),
+    Infeasible(Infeasibility),
+}
+
+impl std::fmt::Display for ConfigureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Schedule(error) => write!(f, "{}", error),
+            Self::Infeasible(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigureError {}
+
+/// The operations shared by the search engines, so that tools and benchmarks can run any of them
+///
+/// Each solver keeps its state in its own format, such as the blocks of `Search` or the flat
+/// seats of `DFScheduler`, and `to_seats` converts it to flat seats.
+pub trait Solver<'a>: Sized {
+    /// Starts a search for `rounds` rounds on tables of the given sizes
+    fn configure(tables: &'a [usize], rounds: usize) -> Result<Self, ConfigureError>;
+
+    fn step(&mut self) -> Result<SearchStep, Infeasibility>;
+
+    /// The current state, in the solver's own format
+    fn get_current(&self) -> &[usize];
+
+    /// A state in the solver's own format, such as `get_current` or the `best` of a
+    /// `BudgetedResult`, as flat seats
+    fn to_seats(&self, state: &[usize]) -> Vec<usize>;
+
+    /// The current state, if it is a complete schedule
+    fn to_solution(&self) -> Option<Solution>;
+
+    fn get_stats(&self) -> &SearchStats;
+
+    fn solve_with_budget(&mut self, budget: Budget) -> BudgetedResult;
+
+    /// Steps until a schedule is found
+    fn find_solution(&mut self) -> Result<Solution, Infeasibility> {
+        while self.step()? != SearchStep::Solved {}
+        Ok(self
+            .to_solution()
+            .expect("Solved state is a complete schedule"))
+    }
+}
+
+impl<'a> Solver<'a> for Search<'a> {
+    fn configure(tables: &'a [usize], rounds: usize) -> Result<Self, ConfigureError> {
+        let schedule = ScheduleBuilder::new(tables)
+            .rounds(rounds)
+            .build()
+            .map_err(ConfigureError::Schedule)?;
+        Search::new(schedule).map_err(ConfigureError::Infeasible)
+    }
+
+    fn step(&mut self) -> Result<SearchStep, Infeasibility> {
+        Search::step(self)
+    }
+
+    fn get_current(&self) -> &[usize] {
+        Search::get_current(self)
+    }
+
+    fn to_seats(&self, state: &[usize]) -> Vec<usize> {
+        self.get_scheduler().to_seats(state)
+    }
+
+    fn to_solution(&self) -> Option<Solution> {
+        self.get_scheduler().to_solution(Search::get_current(self))
+    }
+
+    fn get_stats(&self) -> &SearchStats {
+        Search::get_stats(self)
+    }
+
+    fn solve_with_budget(&mut self, budget: Budget) -> BudgetedResult {
+        Search::solve_with_budget(self, budget)
+    }
+}
+
+impl<'a, T: Word> Solver<'a> for DFScheduler<T> {
+    fn configure(tables: &'a [usize], rounds: usize) -> Result<Self, ConfigureError> {
+        let schedule = ScheduleBuilder::new(tables)
+            .rounds(rounds)
+            .build()
+            .map_err(ConfigureError::Schedule)?;
+        schedule
+            .check_feasibility()
+            .map_err(ConfigureError::Infeasible)?;
+        let groups = tables
+            .iter()
+            .filter_map(|size| std::num::NonZeroUsize::new(*size))
+            .collect::<Vec<_>>();
+        Ok(DFScheduler::new(&groups).with_rounds(rounds))
+    }
+
+    fn step(&mut self) -> Result<SearchStep, Infeasibility> {
+        match DFScheduler::step(self) {
+            Some(DFStep::Complete) => Ok(SearchStep::Solved),
+            Some(DFStep::Advanced(_)) => Ok(SearchStep::Advanced),
+            Some(DFStep::Backtracked) => Ok(SearchStep::Backtracked),
+            None => Err(Infeasibility::SearchExhausted {
+                steps: self.get_stats().nodes + self.get_stats().backtracks,
+            }),
+        }
+    }
+
+    fn get_current(&self) -> &[usize] {
+        self.get_schedule()
+    }
+
+    fn to_seats(&self, state: &[usize]) -> Vec<usize> {
+        state.to_vec()
+    }
+
+    fn to_solution(&self) -> Option<Solution> {
+        if self.is_complete() {
+            Some(self.get_solution())
+        } else {
+            None
+        }
+    }
+
+    fn get_stats(&self) -> &SearchStats {
+        DFScheduler::get_stats(self)
+    }
+
+    fn solve_with_budget(&mut self, budget: Budget) -> BudgetedResult {
+        DFScheduler::solve_with_budget(self, budget)
+    }
+}